@@ -0,0 +1,35 @@
+/* Exercises the C ABI, see the FFI section of the README for how to build and run it */
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "passchecker.h"
+
+int main(void) {
+    assert(passchecker_check("password") == 0);
+    assert(passchecker_check("w7#Kq!vZ2m@Lp9xR") == 1);
+    assert(passchecker_check(NULL) == -1);
+    assert(passchecker_check("\xff") == -1);
+
+    PasscheckerResults *results = passchecker_results("password");
+    assert(results != NULL);
+    assert(passchecker_results_accepted(results) == 0);
+    size_t len = passchecker_results_len(results);
+    assert(len > 0);
+    int failures = 0;
+    for (size_t i = 0; i < len; i++) {
+        int32_t outcome = passchecker_result_outcome(results, i);
+        assert(outcome >= 0 && outcome <= 4);
+        failures += outcome == 2;
+        printf("%s: %d %s\n", passchecker_result_name(results, i), outcome,
+               passchecker_result_info(results, i));
+    }
+    assert(failures > 0);
+    assert(passchecker_result_name(results, len) == NULL);
+    assert(passchecker_result_outcome(results, len) == -1);
+    passchecker_results_free(results);
+    passchecker_results_free(NULL);
+
+    puts("ok");
+    return 0;
+}
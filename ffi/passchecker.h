@@ -0,0 +1,29 @@
+/* C declarations of the passchecker library, built with `cargo build --release --features ffi` */
+#ifndef PASSCHECKER_H
+#define PASSCHECKER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+/* The results of every test, owned by the library */
+typedef struct PasscheckerResults PasscheckerResults;
+
+/* 1 if the password was accepted, 0 if it wasn't, -1 if it is NULL or not valid UTF-8 */
+int32_t passchecker_check(const char *password);
+
+/* The results of every test, NULL if the password is NULL or not valid UTF-8.
+ * Free them with passchecker_results_free */
+PasscheckerResults *passchecker_results(const char *password);
+size_t passchecker_results_len(const PasscheckerResults *results);
+/* 1 if the password was accepted, 0 otherwise */
+int32_t passchecker_results_accepted(const PasscheckerResults *results);
+
+/* The strings live as long as the results, NULL if index is out of bounds */
+const char *passchecker_result_name(const PasscheckerResults *results, size_t index);
+const char *passchecker_result_info(const PasscheckerResults *results, size_t index);
+/* 0 success, 1 warning, 2 failure, 3 ignored, 4 advisory failure, -1 if index is out of bounds */
+int32_t passchecker_result_outcome(const PasscheckerResults *results, size_t index);
+
+void passchecker_results_free(PasscheckerResults *results);
+
+#endif
@@ -0,0 +1,30 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use passchecker::{best_match, Algorithm, Checker, Ignore, Policy, TieBreak};
+
+#[derive(Arbitrary, Debug)]
+struct Input<'a> {
+    password: &'a [u8],
+    wordlist: Vec<&'a str>,
+    min_length: u8,
+    similarity: u8,
+}
+
+fuzz_target!(|input: Input| {
+    // Passwords read from STDIN or files aren't guaranteed to be valid UTF-8
+    let password = String::from_utf8_lossy(input.password);
+    let mut policy = Policy::default();
+    policy.min_length = input.min_length;
+    policy.similarity = input.similarity.min(100);
+    // Scanning the internal wordlist on every run is far too slow, so the collision logic is
+    // exercised against the generated wordlist instead
+    policy.ignore = vec![Ignore::WordlistCollisions];
+    let checker = Checker::new(policy).expect("No files to read");
+    checker.check(&password);
+    for tie_break in [TieBreak::Shortest, TieBreak::Longest, TieBreak::Alphabetical] {
+        best_match(&password, &input.wordlist, tie_break, Algorithm::Lcs);
+    }
+    best_match(&password, &input.wordlist, TieBreak::FirstInFile, Algorithm::Weighted);
+});
@@ -0,0 +1,98 @@
+//! Counting the characters of a password by class, for the composition tests
+
+/// How many characters of each class a password contains, every character is counted in exactly
+/// one class
+///
+/// ```
+/// # use passchecker::charclass::ClassCounts;
+/// let counts = ClassCounts::of("Pa55 wörd!");
+/// assert_eq!(
+///     counts,
+///     ClassCounts { lower: 5, upper: 1, digit: 2, symbol: 1, other: 1 }
+/// );
+/// assert_eq!(counts.total(), 10);
+/// assert_eq!(ClassCounts::of(""), ClassCounts::default());
+/// assert_eq!(ClassCounts::of("ÉTÉ").upper, 3);
+/// assert_eq!(ClassCounts::of("€ \t").other, 3);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ClassCounts {
+    /// Lowercase letters, in any script
+    pub lower: usize,
+    /// Uppercase letters, in any script
+    pub upper: usize,
+    /// The ASCII digits
+    pub digit: usize,
+    /// ASCII punctuation and symbols
+    pub symbol: usize,
+    /// Everything else, such as whitespace, caseless letters and non-ASCII symbols
+    pub other: usize,
+}
+
+impl ClassCounts {
+    /// Counts the characters of `password` in a single pass
+    pub fn of(password: &str) -> Self {
+        let mut counts = Self::default();
+        for ch in password.chars() {
+            let class = if ch.is_lowercase() {
+                &mut counts.lower
+            } else if ch.is_uppercase() {
+                &mut counts.upper
+            } else if ch.is_ascii_digit() {
+                &mut counts.digit
+            } else if ch.is_ascii_punctuation() {
+                &mut counts.symbol
+            } else {
+                &mut counts.other
+            };
+            *class += 1;
+        }
+        counts
+    }
+
+    /// The number of characters counted
+    pub fn total(&self) -> usize {
+        self.lower + self.upper + self.digit + self.symbol + self.other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClassCounts;
+
+    #[test]
+    fn empty() {
+        let counts = ClassCounts::of("");
+        assert_eq!(counts, ClassCounts::default());
+        assert_eq!(counts.total(), 0);
+    }
+
+    #[test]
+    fn non_ascii_letters() {
+        let counts = ClassCounts::of("ßäÖΣω");
+        assert_eq!(counts.lower, 3);
+        assert_eq!(counts.upper, 2);
+        assert_eq!(counts.other, 0);
+        // Letters without case are neither lower nor upper
+        let counts = ClassCounts::of("密码한글");
+        assert_eq!(counts.other, 4);
+        assert_eq!(counts.lower + counts.upper, 0);
+    }
+
+    #[test]
+    fn whitespace() {
+        let counts = ClassCounts::of(" \t\n\u{a0}\u{3000}");
+        assert_eq!(counts.other, 5);
+        assert_eq!(counts.symbol, 0);
+        assert_eq!(counts.total(), 5);
+    }
+
+    #[test]
+    fn symbols_only() {
+        let counts = ClassCounts::of("!@#$%^&*()_+-=[]{};':\",./<>?\\|`~");
+        assert_eq!(counts.symbol, 32);
+        assert_eq!(counts.total(), 32);
+        // Symbols outside of ASCII aren't counted as symbols
+        assert_eq!(ClassCounts::of("€£§").other, 3);
+    }
+}
@@ -0,0 +1,188 @@
+//! Reading wordlists that may be compressed with gzip, zstd or bzip2, which breach archives
+//! usually are. The compression is picked by the extension of the file, or by its first bytes if
+//! the extension isn't known, and anything else is read as plain text. Decompression is streamed,
+//! the compressed file is never held in memory.
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// The compressions a wordlist can be read in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Plain,
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// The compression that the extension of `path` stands for, if it is one
+    ///
+    /// ```
+    /// use passchecker::compression::Compression;
+    /// use std::path::Path;
+    ///
+    /// assert_eq!(Compression::from_extension(Path::new("rockyou.txt.gz")), Some(Compression::Gzip));
+    /// assert_eq!(Compression::from_extension(Path::new("list.zst")), Some(Compression::Zstd));
+    /// assert_eq!(Compression::from_extension(Path::new("list.txt")), None);
+    /// ```
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "gz" => Some(Self::Gzip),
+            "zst" => Some(Self::Zstd),
+            "bz2" => Some(Self::Bzip2),
+            _ => None,
+        }
+    }
+
+    /// The compression whose magic bytes `bytes` starts with, [`Compression::Plain`] if none does
+    ///
+    /// ```
+    /// use passchecker::compression::Compression;
+    ///
+    /// assert_eq!(Compression::from_magic(&[0x1f, 0x8b, 0x08]), Compression::Gzip);
+    /// assert_eq!(Compression::from_magic(b"BZh91AY"), Compression::Bzip2);
+    /// assert_eq!(Compression::from_magic(b"password\n"), Compression::Plain);
+    /// ```
+    pub fn from_magic(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            Self::Gzip
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Self::Zstd
+        } else if bytes.starts_with(b"BZh") {
+            Self::Bzip2
+        } else {
+            Self::Plain
+        }
+    }
+}
+
+/// Reads the wordlist at `path`, decompressing it if needed
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+    read_stream(
+        BufReader::new(File::open(path)?),
+        Compression::from_extension(path),
+    )
+}
+
+/// Reads the wordlist at `path` as raw bytes, decompressing it if needed, for wordlists that may
+/// not be valid UTF-8
+pub fn read_bytes(path: &Path) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    decoder(
+        BufReader::new(File::open(path)?),
+        Compression::from_extension(path),
+    )?
+    .read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads a wordlist from `reader`, decompressing it with `compression`, or with the compression
+/// its first bytes point at if that is `None`
+pub fn read_stream(reader: impl BufRead, compression: Option<Compression>) -> io::Result<String> {
+    io::read_to_string(decoder(reader, compression)?)
+}
+
+/// How far reading a wordlist got
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The lines read so far
+    pub lines: usize,
+    /// The bytes read so far, after decompression
+    pub bytes: u64,
+    /// The size of the whole wordlist, only known for plain files. Streams and compressed files
+    /// can only be counted as they are read
+    pub total: Option<u64>,
+}
+
+/// How much of the wordlist is read between two calls to the progress callback
+const PROGRESS_CHUNK: usize = 1 << 16;
+
+/// Reads a wordlist from `reader` like [`read_stream`], calling `report` after every chunk read.
+/// `size` is the size of the input, which is only passed on as [`Progress::total`] if the input
+/// turns out not to be compressed
+///
+/// ```
+/// use passchecker::compression::read_stream_with_progress;
+///
+/// let mut lines = 0;
+/// let wordlist =
+///     read_stream_with_progress(&b"password\ndragon\n"[..], None, Some(16), &mut |progress| {
+///         lines = progress.lines;
+///         assert_eq!(progress.total, Some(16));
+///     })
+///     .unwrap();
+/// assert_eq!((wordlist.as_str(), lines), ("password\ndragon\n", 2));
+/// ```
+pub fn read_stream_with_progress(
+    mut reader: impl BufRead,
+    compression: Option<Compression>,
+    size: Option<u64>,
+    report: &mut dyn FnMut(Progress),
+) -> io::Result<String> {
+    let compression = match compression {
+        Some(compression) => compression,
+        None => Compression::from_magic(reader.fill_buf()?),
+    };
+    let mut progress = Progress {
+        lines: 0,
+        bytes: 0,
+        total: size.filter(|_| compression == Compression::Plain),
+    };
+    let mut decoder = decompress(reader, compression)?;
+    let mut bytes = Vec::new();
+    let mut chunk = vec![0; PROGRESS_CHUNK];
+    loop {
+        let read = match decoder.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+        bytes.extend_from_slice(&chunk[..read]);
+        progress.lines += chunk[..read].iter().filter(|&&byte| byte == b'\n').count();
+        progress.bytes += read as u64;
+        report(progress);
+    }
+    String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// A reader of the decompressed contents of `reader`, detecting the compression if it is `None`
+fn decoder<'a>(
+    mut reader: impl BufRead + 'a,
+    compression: Option<Compression>,
+) -> io::Result<Box<dyn Read + 'a>> {
+    let compression = match compression {
+        Some(compression) => compression,
+        None => Compression::from_magic(reader.fill_buf()?),
+    };
+    decompress(reader, compression)
+}
+
+#[cfg(feature = "compression")]
+fn decompress<'a>(
+    reader: impl BufRead + 'a,
+    compression: Compression,
+) -> io::Result<Box<dyn Read + 'a>> {
+    Ok(match compression {
+        Compression::Plain => Box::new(reader),
+        // Multi-member decoders, so files that were concatenated are read in full
+        Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(reader)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(reader)?),
+        Compression::Bzip2 => Box::new(bzip2::bufread::MultiBzDecoder::new(reader)),
+    })
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress<'a>(
+    reader: impl BufRead + 'a,
+    compression: Compression,
+) -> io::Result<Box<dyn Read + 'a>> {
+    match compression {
+        Compression::Plain => Ok(Box::new(reader)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "compressed wordlists need the compression feature",
+        )),
+    }
+}
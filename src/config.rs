@@ -0,0 +1,1010 @@
+use crate::counts::CountFormat;
+use crate::error::Error;
+use crate::{
+    Algorithm, CrackTime, CrackTimes, Ignore, Normalization, Outcome, Severity, TestResult,
+    TieBreak, Tier, Transformation, DEFAULT_BREACH_THRESHOLD, DEFAULT_COMMENT_PREFIX,
+    DEFAULT_COMPRESSIBILITY_MIN_LENGTH, DEFAULT_MATCH_DISPLAY_THRESHOLD,
+    DEFAULT_MAX_CHARACTER_SHARE, DEFAULT_MAX_DICTIONARY_SHARE, DEFAULT_MIN_COMPRESSION_RATIO,
+    DEFAULT_MIN_LENGTH, DEFAULT_MIN_LOG10_GUESSES, DEFAULT_MIN_NGRAM_BITS, DEFAULT_MIN_WORDS,
+    DEFAULT_OFFLINE_RATE, DEFAULT_ONLINE_RATE, DEFAULT_SIMILARITY, DEFAULT_SUBSTRING_MIN_LENGTH,
+    DEFAULT_TIERS, MAX_REASONABLE_LENGTH,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+/// A policy file as passed with `--config`, every section and key is optional
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// The preset policy the rest of the file is applied on top of
+    pub profile: Option<Profile>,
+    /// Which tests to ignore, using the same names as `--ignore`
+    pub ignore: Option<Vec<Ignore>>,
+    /// Which tests are only advisory, using the same names as `--ignore`. Their failures are
+    /// reported but don't count towards whether the password is accepted
+    pub advisory: Option<Vec<Ignore>>,
+    /// A file of pre-approved passwords, one per line, that skip all tests
+    pub allowlist: Option<PathBuf>,
+    /// A directory of downloaded Pwned Passwords SHA-1 range files to look the password up in
+    pub pwned_dir: Option<PathBuf>,
+    /// A file of previous passwords, one per line, that the password shouldn't match or resemble.
+    /// Lines starting with `sha1:` are SHA-1 hashes in hexadecimal, which are only matched exactly
+    pub history: Option<PathBuf>,
+    /// An index built with `passchecker build-index` to look the password up in exactly
+    pub index: Option<PathBuf>,
+    /// Checks the password as a passphrase of whitespace-separated words
+    pub passphrase: Option<bool>,
+    /// Only allows ASCII characters in the password
+    pub ascii_only: Option<bool>,
+    /// How many tests have to pass for the password to be accepted, defaults to all of them
+    pub min_passed: Option<u8>,
+    /// How many milliseconds the tests of a password may take in total, the ones that haven't
+    /// started by then are skipped
+    pub max_time_ms: Option<u64>,
+    /// A service from [`crate::service::SERVICES`] whose password rules the password has to meet
+    pub service: Option<String>,
+    /// The weakest strength tier a password is accepted in. Once set it alone decides whether the
+    /// password is accepted, however many tests pass
+    pub min_tier: Option<Tier>,
+    /// The severity of the tests, using the same names as `--ignore`, for the ones whose default
+    /// from [`Ignore::severity`] doesn't fit
+    pub severities: BTreeMap<Ignore, Severity>,
+    pub tiers: Tiers,
+    pub guess_rates: GuessRates,
+    pub minimum_chars: MinimumChars,
+    pub maximum_chars: MaximumChars,
+    pub wordlist_collisions: WordlistCollisions,
+    pub normalized_collisions: NormalizedCollisions,
+    pub character_share: CharacterShare,
+    pub unique_chars: UniqueChars,
+    pub compressibility: Compressibility,
+    pub dictionary_share: DictionaryShare,
+    pub pattern_score: PatternScore,
+    pub ngram_entropy: NgramEntropy,
+    pub word_count: WordCount,
+    pub context: Context,
+    pub banned: Banned,
+    pub default_passwords: DefaultPasswords,
+    pub regex: Regexes,
+    pub account: Account,
+    pub substrings: Substrings,
+    pub concatenated_words: ConcatenatedWords,
+    pub word_pairs: WordPairs,
+    pub transformed_collisions: TransformedCollisions,
+    pub leet_collisions: LeetCollisions,
+    pub confusables: Confusables,
+    pub cracklib: Cracklib,
+    pub breach_counts: BreachCounts,
+}
+
+/// The estimated guesses, as powers of 10, from which a password falls in each strength tier
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Tiers {
+    pub weak: Option<u8>,
+    pub fair: Option<u8>,
+    pub strong: Option<u8>,
+    pub very_strong: Option<u8>,
+}
+
+/// The guess rates crack times are estimated at
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct GuessRates {
+    /// Guesses per second against a login that throttles attempts, defaults to 100
+    pub online: Option<u64>,
+    /// Guesses per second against a leaked fast hash, defaults to 10^10
+    pub offline: Option<u64>,
+}
+
+/// Parameters of the minimum length test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MinimumChars {
+    /// The minimum length of the password
+    pub length: Option<u8>,
+}
+
+/// Parameters of the maximum length test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MaximumChars {
+    /// The maximum length of the password, not checked unless set
+    pub length: Option<u8>,
+}
+
+/// Parameters of the wordlist collision test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct WordlistCollisions {
+    /// What wordlists to check against, defaults to the internal wordlist
+    pub wordlists: Option<Vec<PathBuf>>,
+    /// The minimum percentage match required for a match to be considered a collision
+    pub similarity: Option<u8>,
+    /// The similarity percentage from which a match below `similarity` is reported as a warning
+    pub warn_similarity: Option<u8>,
+    /// Whether to skip blank lines and comments in the wordlist
+    pub skip_comments: Option<bool>,
+    /// Whether to remove the repeated entries of each wordlist when it is read
+    pub dedup: Option<bool>,
+    /// The length under which wordlist entries are skipped, 0 keeps all of them
+    pub min_length: Option<u8>,
+    /// What comment lines start with, defaults to `#`
+    pub comment_prefix: Option<String>,
+    /// The similarity percentage below which the best match isn't worth reporting
+    pub match_display_threshold: Option<u8>,
+    /// Report the highest similarity found instead of passing or failing
+    pub report_threshold: Option<bool>,
+    /// Show the file and line the best match was found on
+    pub show_match_location: Option<bool>,
+    /// How similarity is computed
+    pub algorithm: Option<Algorithm>,
+    /// Which of several equally similar entries is reported as the best match
+    pub tie_break: Option<TieBreak>,
+    /// Show the rank of the best match, its position in a wordlist ordered by commonality
+    pub show_match_rank: Option<bool>,
+    /// Fail outright if the best match is one of this many most common entries of its wordlist
+    pub fail_top: Option<u32>,
+    /// Check the wordlists in order and stop at the first one with a collision
+    pub stop_on_hit: Option<bool>,
+}
+
+/// Parameters of the normalized wordlist collision test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NormalizedCollisions {
+    /// Which transformations to undo before comparing, defaults to all of them
+    pub normalizations: Option<Vec<Normalization>>,
+}
+
+/// Parameters of the single character share test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CharacterShare {
+    /// The largest percentage of the password a single character may make up
+    pub max_percentage: Option<u8>,
+}
+
+/// Parameters of the distinct characters test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct UniqueChars {
+    /// How many distinct characters the password needs, the test only runs if this is set
+    pub min_unique: Option<u8>,
+}
+
+/// Parameters of the compressibility test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Compressibility {
+    /// The length from which the password is checked, shorter ones are too short to compress
+    pub min_length: Option<u8>,
+    /// The smallest percentage of its length the password may compress to
+    pub min_ratio: Option<u8>,
+}
+
+/// Parameters of the dictionary word share test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct DictionaryShare {
+    /// The largest percentage of the password a single wordlist entry may make up
+    pub max_percentage: Option<u8>,
+}
+
+/// Parameters of the pattern score test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PatternScore {
+    /// The estimated guesses the password has to take, as a power of 10
+    pub min_log10_guesses: Option<u8>,
+}
+
+/// Parameters of the n-gram entropy test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct NgramEntropy {
+    /// Whether to estimate the entropy of the password from English letter pair frequencies
+    pub enabled: Option<bool>,
+    /// The entropy in bits the password has to have
+    pub min_bits: Option<u8>,
+}
+
+/// Parameters of the passphrase word count test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct WordCount {
+    /// The minimum number of words in a passphrase
+    pub min_words: Option<u8>,
+}
+
+/// Parameters of the contextual terms test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Context {
+    /// Terms related to where the password is used, such as the site name
+    pub terms: Option<Vec<String>>,
+}
+
+/// Parameters of the banned terms test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Banned {
+    /// Terms a password must neither be nor contain, failing it without running the other tests
+    pub terms: Option<Vec<String>>,
+}
+
+/// Parameters of the default passwords test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DefaultPasswords {
+    /// Whether to compare the password against the built-in list of default passwords
+    pub enabled: Option<bool>,
+    /// More default passwords to compare against, such as the ones of in-house systems
+    pub extra: Option<Vec<String>>,
+}
+
+/// Parameters of the required and rejected formats tests
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Regexes {
+    /// Regular expressions the password has to match, every one of them
+    #[serde(rename = "match")]
+    pub matches: Option<Vec<String>>,
+    /// Regular expressions the password may not match, none of them
+    pub reject: Option<Vec<String>>,
+}
+
+/// Parameters of the username and email address test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Account {
+    /// The username the password belongs to
+    pub username: Option<String>,
+    /// The email address the password belongs to, only its local part is compared
+    pub email: Option<String>,
+}
+
+/// Parameters of the embedded wordlist entries test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Substrings {
+    /// Whether to look for wordlist entries embedded anywhere in the password
+    pub enabled: Option<bool>,
+    /// The length of the shortest wordlist entry worth looking for, defaults to 4
+    pub min_length: Option<u8>,
+}
+
+/// Parameters of the pairs of wordlist entries test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WordPairs {
+    /// Whether to look for passwords that are two entries close to the wordlist put together
+    pub enabled: Option<bool>,
+}
+
+/// Parameters of the concatenated wordlist entries test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConcatenatedWords {
+    /// Whether to look for passwords made up entirely of wordlist entries
+    pub enabled: Option<bool>,
+}
+
+/// Parameters of the transformed wordlist collision test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransformedCollisions {
+    /// Which transformations of the password to compare against the wordlist, the test doesn't
+    /// run unless some are given
+    pub transformations: Option<Vec<Transformation>>,
+}
+
+/// Parameters of the leet variants test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LeetCollisions {
+    /// Whether to look the de-leeted password up in the de-leeted wordlist exactly
+    pub enabled: Option<bool>,
+}
+
+/// Parameters of the look-alike characters test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Confusables {
+    /// Whether to warn about characters from other scripts that look like ASCII ones, and compare
+    /// the password written in ASCII against the wordlist
+    pub enabled: Option<bool>,
+}
+
+/// Parameters of the cracklib dictionary test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Cracklib {
+    /// Whether to compare the password against the cracklib dictionary
+    pub enabled: Option<bool>,
+    /// The dictionary to use, a packed one without its `.pwd` extension or a word file, found in
+    /// the standard paths unless set
+    pub dictionary: Option<PathBuf>,
+}
+
+/// Parameters of the breach count test
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BreachCounts {
+    /// A wordlist of passwords along with how often they were seen in breaches
+    pub wordlist: Option<PathBuf>,
+    /// How the counts are written, `uniq-c` by default
+    pub format: Option<CountFormat>,
+    /// The count over which a password fails
+    pub threshold: Option<u64>,
+}
+
+impl Config {
+    /// Reads and validates the config file at `path`
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let contents = read_to_string(path).map_err(|source| Error::ReadConfig {
+            path: path.to_owned(),
+            source,
+        })?;
+        let config: Config = toml::from_str(&contents).map_err(|source| Error::ParseConfig {
+            path: path.to_owned(),
+            source,
+        })?;
+        match config.invalid_percentage() {
+            Some((key, value)) => Err(Error::InvalidPercentage {
+                path: path.to_owned(),
+                key,
+                value,
+            }),
+            None => Ok(config),
+        }
+    }
+
+    /// Parses and validates a config given inline as a JSON object, with the same keys as the
+    /// config file
+    ///
+    /// ```
+    /// use passchecker::Config;
+    ///
+    /// let config = Config::from_json(r#"{"minimum-chars": {"length": 16}}"#).unwrap();
+    /// assert_eq!(config.minimum_chars.length, Some(16));
+    /// assert!(Config::from_json(r#"{"minimum-chars": {"lenght": 16}}"#).is_err());
+    /// assert!(Config::from_json(r#"{"wordlist-collisions": {"similarity": 120}}"#).is_err());
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let config: Config =
+            serde_json::from_str(json).map_err(|source| Error::ParsePolicyJson { source })?;
+        match config.invalid_percentage() {
+            Some((key, value)) => Err(Error::InvalidPolicyJson { key, value }),
+            None => Ok(config),
+        }
+    }
+
+    /// The first key holding a percentage over 100, along with its value
+    fn invalid_percentage(&self) -> Option<(&'static str, u8)> {
+        let percentages = [
+            (
+                "wordlist-collisions.similarity",
+                self.wordlist_collisions.similarity,
+            ),
+            (
+                "wordlist-collisions.warn-similarity",
+                self.wordlist_collisions.warn_similarity,
+            ),
+            (
+                "character-share.max-percentage",
+                self.character_share.max_percentage,
+            ),
+            ("compressibility.min-ratio", self.compressibility.min_ratio),
+            (
+                "dictionary-share.max-percentage",
+                self.dictionary_share.max_percentage,
+            ),
+            (
+                "wordlist-collisions.match-display-threshold",
+                self.wordlist_collisions.match_display_threshold,
+            ),
+        ];
+        percentages
+            .into_iter()
+            .find_map(|(key, value)| value.filter(|&value| value > 100).map(|value| (key, value)))
+    }
+}
+
+/// Preset policies following well-known standards
+#[derive(clap::ValueEnum, Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Profile {
+    /// NIST SP 800-63B: at least 8 characters, no composition rules, checked against blocklists of
+    /// common, default and leet-spelled passwords and the dictionary words they contain
+    Nist,
+    /// PCI DSS 4.0: at least 12 characters containing numbers, and no vendor default passwords
+    Pci,
+    /// At least 16 characters, collisions from 80% similarity, and the substrings, leet
+    /// collisions, look-alikes and default passwords tests enabled along with every normalization
+    Strict,
+}
+
+impl Profile {
+    /// The policy the profile sets before the config file and command line flags are applied
+    pub fn policy(self) -> Policy {
+        let mut policy = Policy::default();
+        match self {
+            Profile::Nist => {
+                policy.min_length = 8;
+                policy.ignore = vec![Ignore::Numbers, Ignore::SpecialChars];
+                policy.default_passwords = true;
+                policy.leet_collisions = true;
+                policy.substrings = true;
+            }
+            Profile::Pci => {
+                policy.min_length = 12;
+                policy.ignore = vec![Ignore::SpecialChars];
+                policy.default_passwords = true;
+            }
+            Profile::Strict => {
+                policy.min_length = 16;
+                policy.similarity = 80;
+                policy.normalizations = Normalization::ALL.to_vec();
+                policy.default_passwords = true;
+                policy.leet_collisions = true;
+                policy.substrings = true;
+                policy.confusables = true;
+            }
+        }
+        policy
+    }
+}
+
+/// The parameters the tests actually run with, after the command line flags were applied on top
+/// of the config file, which itself is applied on top of the profile
+#[derive(Debug, Clone)]
+pub struct Policy {
+    pub min_length: u8,
+    pub max_length: Option<u8>,
+    pub similarity: u8,
+    pub warn_similarity: Option<u8>,
+    pub wordlists: Vec<PathBuf>,
+    pub skip_comments: bool,
+    pub dedup_wordlist: bool,
+    pub wordlist_min_length: u8,
+    pub comment_prefix: String,
+    pub match_display_threshold: u8,
+    pub report_threshold: bool,
+    pub show_match_location: bool,
+    pub algorithm: Algorithm,
+    pub tie_break: TieBreak,
+    pub show_match_rank: bool,
+    pub fail_top: Option<u32>,
+    pub wordlist_stop_on_hit: bool,
+    pub normalizations: Vec<Normalization>,
+    pub max_character_share: u8,
+    pub min_unique: Option<u8>,
+    pub compressibility_min_length: u8,
+    pub min_compression_ratio: u8,
+    pub max_dictionary_share: u8,
+    pub min_log10_guesses: u8,
+    /// Whether the n-gram entropy test runs
+    pub ngram_entropy: bool,
+    pub min_ngram_bits: u8,
+    pub ignore: Vec<Ignore>,
+    pub advisory: Vec<Ignore>,
+    pub allowlist: Option<PathBuf>,
+    pub pwned_dir: Option<PathBuf>,
+    pub history: Option<PathBuf>,
+    pub index: Option<PathBuf>,
+    pub passphrase: bool,
+    pub ascii_only: bool,
+    pub min_words: u8,
+    pub context: Vec<String>,
+    pub banned: Vec<String>,
+    pub default_passwords: bool,
+    pub extra_default_passwords: Vec<String>,
+    /// Regular expressions the password has to match, checked by the required formats test
+    pub match_regex: Vec<String>,
+    /// Regular expressions the password may not match, checked by the rejected formats test
+    pub reject_regex: Vec<String>,
+    pub username: Option<String>,
+    pub email: Option<String>,
+    pub substrings: bool,
+    pub substring_min_length: u8,
+    pub concatenated_words: bool,
+    pub word_pairs: bool,
+    pub transformations: Vec<Transformation>,
+    pub leet_collisions: bool,
+    pub confusables: bool,
+    pub cracklib: bool,
+    pub cracklib_dictionary: Option<PathBuf>,
+    pub breach_counts: Option<PathBuf>,
+    pub count_format: CountFormat,
+    pub breach_threshold: u64,
+    pub min_passed: Option<u8>,
+    pub max_time_ms: Option<u64>,
+    pub service: Option<String>,
+    /// The weakest tier a password is accepted in, see [`crate::Checker::accepts`]
+    pub min_tier: Option<Tier>,
+    /// The severities of the tests that don't have their default one, see [`Policy::severity`]
+    pub severities: BTreeMap<Ignore, Severity>,
+    /// The estimated guesses, as powers of 10, from which a password is Weak, Fair, Strong and Very
+    /// Strong, see [`Policy::tier`]
+    pub tiers: [u8; 4],
+    /// The guesses per second of an online attacker, see [`Policy::crack_times`]
+    pub online_rate: u64,
+    /// The guesses per second of an offline attacker
+    pub offline_rate: u64,
+}
+
+impl Policy {
+    /// The similarity, in the 0-1 range, from which a match is considered a collision
+    pub fn similarity_threshold(&self) -> f64 {
+        self.similarity.min(99) as f64 / 100.0
+    }
+
+    /// Whether a password that passed `passed` out of `enabled` tests is accepted
+    pub fn passes(&self, passed: usize, enabled: usize) -> bool {
+        match self.min_passed {
+            Some(min_passed) => passed >= min_passed.into(),
+            None => passed == enabled,
+        }
+    }
+
+    /// Whether a password with `results` is accepted. Only the required tests count, the ones
+    /// that aren't in [`Policy::advisory`]
+    pub fn accepts(&self, results: &[TestResult]) -> bool {
+        // A banned term fails the password even when only some of the tests have to pass
+        if results
+            .iter()
+            .any(|x| x.ignore == Ignore::Banned && x.outcome == Outcome::Failure)
+        {
+            return false;
+        }
+        let required: Vec<&TestResult> = results
+            .iter()
+            .filter(|x| {
+                !matches!(x.outcome, Outcome::Ignored | Outcome::Advisory)
+                    && !self.advisory.contains(&x.ignore)
+            })
+            .collect();
+        let passed = required.iter().filter(|x| x.outcome.passed()).count();
+        self.passes(passed, required.len())
+    }
+
+    /// The tier a password that takes 10^`log10_guesses` guesses falls in, the strongest one whose
+    /// threshold in [`Policy::tiers`] it reaches
+    ///
+    /// ```
+    /// use passchecker::{Policy, Tier};
+    ///
+    /// let policy = Policy::default();
+    /// assert_eq!(policy.tier(2.5), Tier::VeryWeak);
+    /// assert_eq!(policy.tier(6.0), Tier::Fair);
+    /// assert_eq!(policy.tier(18.9), Tier::VeryStrong);
+    /// ```
+    pub fn tier(&self, log10_guesses: f64) -> Tier {
+        let reached = self
+            .tiers
+            .iter()
+            .filter(|&&threshold| log10_guesses >= threshold.into())
+            .count();
+        Tier::ALL[reached]
+    }
+
+    /// How long `10^log10_guesses` guesses take at [`Policy::online_rate`] and
+    /// [`Policy::offline_rate`]
+    pub fn crack_times(&self, log10_guesses: f64) -> CrackTimes {
+        CrackTimes {
+            online: CrackTime::new(log10_guesses, self.online_rate),
+            offline: CrackTime::new(log10_guesses, self.offline_rate),
+        }
+    }
+
+    /// The severity of `test`, from [`Policy::severities`] or its default
+    ///
+    /// ```
+    /// use passchecker::{Ignore, Policy, Severity};
+    ///
+    /// let mut policy = Policy::default();
+    /// assert_eq!(policy.severity(&Ignore::Pwned), Severity::Critical);
+    /// policy.severities.insert(Ignore::Numbers, Severity::High);
+    /// assert_eq!(policy.severity(&Ignore::Numbers), Severity::High);
+    /// ```
+    pub fn severity(&self, test: &Ignore) -> Severity {
+        self.severities
+            .get(test)
+            .copied()
+            .unwrap_or_else(|| test.severity())
+    }
+
+    /// Whether the collision test only has to look for wordlist entries identical to the password,
+    /// which it then does through a map instead of comparing the password to every entry. That is
+    /// the case when the similarity is 100% and nothing else needs the similarity of the best
+    /// match: no warning similarity, no threshold report and no failing on the most common entries
+    ///
+    /// ```
+    /// use passchecker::Policy;
+    ///
+    /// let mut policy = Policy { similarity: 100, ..Policy::default() };
+    /// assert!(policy.exact_collisions());
+    /// policy.warn_similarity = Some(90);
+    /// assert!(!policy.exact_collisions());
+    /// assert!(!Policy::default().exact_collisions());
+    /// ```
+    pub fn exact_collisions(&self) -> bool {
+        self.similarity >= 100
+            && self.warn_similarity.is_none()
+            && !self.report_threshold
+            && self.fail_top.is_none()
+    }
+
+    /// Describes the length settings that make the length tests meaningless, such as a minimum of
+    /// 0 or a maximum below the minimum. The tests still run as configured
+    ///
+    /// ```
+    /// use passchecker::Policy;
+    ///
+    /// let policy = |min_length, max_length| Policy {
+    ///     min_length,
+    ///     max_length,
+    ///     ..Policy::default()
+    /// };
+    /// assert_eq!(policy(0, None).warnings().len(), 1);
+    /// assert!(policy(1, None).warnings().is_empty());
+    /// assert!(policy(128, None).warnings().is_empty());
+    /// assert_eq!(policy(129, None).warnings().len(), 1);
+    /// assert!(policy(12, Some(12)).warnings().is_empty());
+    /// assert_eq!(policy(12, Some(11)).warnings().len(), 1);
+    /// assert_eq!(policy(12, Some(0)).warnings().len(), 2);
+    /// ```
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if !self.ignore.contains(&Ignore::MinimumChars) {
+            if self.min_length == 0 {
+                warnings.push(
+                    "A minimum length of 0 accepts any password, even an empty one".to_string(),
+                );
+            } else if self.min_length > MAX_REASONABLE_LENGTH {
+                warnings.push(format!(
+                    "A minimum length of {} is longer than almost any password, hardly any will pass",
+                    self.min_length
+                ));
+            }
+        }
+        if let (Some(max_length), false) =
+            (self.max_length, self.ignore.contains(&Ignore::MaximumChars))
+        {
+            if max_length == 0 {
+                warnings.push("A maximum length of 0 rejects every password".to_string());
+            }
+            if max_length < self.min_length && !self.ignore.contains(&Ignore::MinimumChars) {
+                warnings.push(format!(
+                    "The maximum length of {max_length} is below the minimum length of {}, no password can pass both",
+                    self.min_length
+                ));
+            }
+        }
+        warnings
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            min_length: DEFAULT_MIN_LENGTH,
+            max_length: None,
+            similarity: DEFAULT_SIMILARITY,
+            warn_similarity: None,
+            wordlists: Vec::new(),
+            skip_comments: false,
+            dedup_wordlist: false,
+            wordlist_min_length: 0,
+            comment_prefix: DEFAULT_COMMENT_PREFIX.to_string(),
+            match_display_threshold: DEFAULT_MATCH_DISPLAY_THRESHOLD,
+            report_threshold: false,
+            show_match_location: false,
+            algorithm: Algorithm::default(),
+            tie_break: TieBreak::default(),
+            show_match_rank: false,
+            fail_top: None,
+            wordlist_stop_on_hit: false,
+            normalizations: Normalization::ALL.to_vec(),
+            max_character_share: DEFAULT_MAX_CHARACTER_SHARE,
+            min_unique: None,
+            compressibility_min_length: DEFAULT_COMPRESSIBILITY_MIN_LENGTH,
+            min_compression_ratio: DEFAULT_MIN_COMPRESSION_RATIO,
+            max_dictionary_share: DEFAULT_MAX_DICTIONARY_SHARE,
+            min_log10_guesses: DEFAULT_MIN_LOG10_GUESSES,
+            ngram_entropy: false,
+            min_ngram_bits: DEFAULT_MIN_NGRAM_BITS,
+            ignore: Vec::new(),
+            advisory: Vec::new(),
+            allowlist: None,
+            pwned_dir: None,
+            history: None,
+            index: None,
+            passphrase: false,
+            ascii_only: false,
+            min_words: DEFAULT_MIN_WORDS,
+            context: Vec::new(),
+            banned: Vec::new(),
+            default_passwords: true,
+            extra_default_passwords: Vec::new(),
+            match_regex: Vec::new(),
+            reject_regex: Vec::new(),
+            username: None,
+            email: None,
+            substrings: false,
+            substring_min_length: DEFAULT_SUBSTRING_MIN_LENGTH,
+            concatenated_words: false,
+            word_pairs: false,
+            transformations: Vec::new(),
+            leet_collisions: false,
+            confusables: false,
+            cracklib: false,
+            cracklib_dictionary: None,
+            breach_counts: None,
+            count_format: CountFormat::default(),
+            breach_threshold: DEFAULT_BREACH_THRESHOLD,
+            min_passed: None,
+            max_time_ms: None,
+            service: None,
+            min_tier: None,
+            severities: BTreeMap::new(),
+            tiers: DEFAULT_TIERS,
+            online_rate: DEFAULT_ONLINE_RATE,
+            offline_rate: DEFAULT_OFFLINE_RATE,
+        }
+    }
+}
+
+impl From<Config> for Policy {
+    fn from(config: Config) -> Self {
+        let base = config.profile.map(Profile::policy).unwrap_or_default();
+        let collisions = config.wordlist_collisions;
+        Self {
+            min_length: config.minimum_chars.length.unwrap_or(base.min_length),
+            max_length: config.maximum_chars.length.or(base.max_length),
+            similarity: collisions.similarity.unwrap_or(base.similarity),
+            warn_similarity: collisions.warn_similarity.or(base.warn_similarity),
+            wordlists: collisions.wordlists.unwrap_or(base.wordlists),
+            skip_comments: collisions.skip_comments.unwrap_or(base.skip_comments),
+            dedup_wordlist: collisions.dedup.unwrap_or(base.dedup_wordlist),
+            wordlist_min_length: collisions.min_length.unwrap_or(base.wordlist_min_length),
+            comment_prefix: collisions.comment_prefix.unwrap_or(base.comment_prefix),
+            match_display_threshold: collisions
+                .match_display_threshold
+                .unwrap_or(base.match_display_threshold),
+            report_threshold: collisions.report_threshold.unwrap_or(base.report_threshold),
+            show_match_location: collisions
+                .show_match_location
+                .unwrap_or(base.show_match_location),
+            algorithm: collisions.algorithm.unwrap_or(base.algorithm),
+            tie_break: collisions.tie_break.unwrap_or(base.tie_break),
+            show_match_rank: collisions.show_match_rank.unwrap_or(base.show_match_rank),
+            fail_top: collisions.fail_top.or(base.fail_top),
+            wordlist_stop_on_hit: collisions.stop_on_hit.unwrap_or(base.wordlist_stop_on_hit),
+            normalizations: config
+                .normalized_collisions
+                .normalizations
+                .unwrap_or(base.normalizations),
+            max_character_share: config
+                .character_share
+                .max_percentage
+                .unwrap_or(base.max_character_share),
+            min_unique: config.unique_chars.min_unique.or(base.min_unique),
+            compressibility_min_length: config
+                .compressibility
+                .min_length
+                .unwrap_or(base.compressibility_min_length),
+            min_compression_ratio: config
+                .compressibility
+                .min_ratio
+                .unwrap_or(base.min_compression_ratio),
+            max_dictionary_share: config
+                .dictionary_share
+                .max_percentage
+                .unwrap_or(base.max_dictionary_share),
+            min_log10_guesses: config
+                .pattern_score
+                .min_log10_guesses
+                .unwrap_or(base.min_log10_guesses),
+            ngram_entropy: config.ngram_entropy.enabled.unwrap_or(base.ngram_entropy),
+            min_ngram_bits: config.ngram_entropy.min_bits.unwrap_or(base.min_ngram_bits),
+            ignore: config.ignore.unwrap_or(base.ignore),
+            advisory: config.advisory.unwrap_or(base.advisory),
+            allowlist: config.allowlist.or(base.allowlist),
+            pwned_dir: config.pwned_dir.or(base.pwned_dir),
+            history: config.history.or(base.history),
+            index: config.index.or(base.index),
+            passphrase: config.passphrase.unwrap_or(base.passphrase),
+            ascii_only: config.ascii_only.unwrap_or(base.ascii_only),
+            min_words: config.word_count.min_words.unwrap_or(base.min_words),
+            context: config.context.terms.unwrap_or(base.context),
+            banned: config.banned.terms.unwrap_or(base.banned),
+            default_passwords: config
+                .default_passwords
+                .enabled
+                .unwrap_or(base.default_passwords),
+            extra_default_passwords: config
+                .default_passwords
+                .extra
+                .unwrap_or(base.extra_default_passwords),
+            match_regex: config.regex.matches.unwrap_or(base.match_regex),
+            reject_regex: config.regex.reject.unwrap_or(base.reject_regex),
+            username: config.account.username.or(base.username),
+            email: config.account.email.or(base.email),
+            substrings: config.substrings.enabled.unwrap_or(base.substrings),
+            substring_min_length: config
+                .substrings
+                .min_length
+                .unwrap_or(base.substring_min_length),
+            concatenated_words: config
+                .concatenated_words
+                .enabled
+                .unwrap_or(base.concatenated_words),
+            word_pairs: config.word_pairs.enabled.unwrap_or(base.word_pairs),
+            transformations: config
+                .transformed_collisions
+                .transformations
+                .unwrap_or(base.transformations),
+            leet_collisions: config
+                .leet_collisions
+                .enabled
+                .unwrap_or(base.leet_collisions),
+            confusables: config.confusables.enabled.unwrap_or(base.confusables),
+            cracklib: config.cracklib.enabled.unwrap_or(base.cracklib),
+            cracklib_dictionary: config.cracklib.dictionary.or(base.cracklib_dictionary),
+            breach_counts: config.breach_counts.wordlist.or(base.breach_counts),
+            count_format: config.breach_counts.format.unwrap_or(base.count_format),
+            breach_threshold: config
+                .breach_counts
+                .threshold
+                .unwrap_or(base.breach_threshold),
+            min_passed: config.min_passed.or(base.min_passed),
+            max_time_ms: config.max_time_ms.or(base.max_time_ms),
+            service: config.service.or(base.service),
+            min_tier: config.min_tier.or(base.min_tier),
+            severities: base
+                .severities
+                .into_iter()
+                .chain(config.severities)
+                .collect(),
+            tiers: [
+                config.tiers.weak.unwrap_or(base.tiers[0]),
+                config.tiers.fair.unwrap_or(base.tiers[1]),
+                config.tiers.strong.unwrap_or(base.tiers[2]),
+                config.tiers.very_strong.unwrap_or(base.tiers[3]),
+            ],
+            online_rate: config.guess_rates.online.unwrap_or(base.online_rate),
+            offline_rate: config.guess_rates.offline.unwrap_or(base.offline_rate),
+        }
+    }
+}
+
+impl From<&Policy> for Config {
+    /// The config file that resolves to `policy`, with every key set
+    fn from(policy: &Policy) -> Self {
+        Self {
+            profile: None,
+            ignore: Some(policy.ignore.clone()),
+            advisory: Some(policy.advisory.clone()),
+            allowlist: policy.allowlist.clone(),
+            pwned_dir: policy.pwned_dir.clone(),
+            history: policy.history.clone(),
+            index: policy.index.clone(),
+            passphrase: Some(policy.passphrase),
+            ascii_only: Some(policy.ascii_only),
+            min_passed: policy.min_passed,
+            max_time_ms: policy.max_time_ms,
+            service: policy.service.clone(),
+            min_tier: policy.min_tier,
+            severities: policy.severities.clone(),
+            tiers: Tiers {
+                weak: Some(policy.tiers[0]),
+                fair: Some(policy.tiers[1]),
+                strong: Some(policy.tiers[2]),
+                very_strong: Some(policy.tiers[3]),
+            },
+            guess_rates: GuessRates {
+                online: Some(policy.online_rate),
+                offline: Some(policy.offline_rate),
+            },
+            minimum_chars: MinimumChars {
+                length: Some(policy.min_length),
+            },
+            maximum_chars: MaximumChars {
+                length: policy.max_length,
+            },
+            wordlist_collisions: WordlistCollisions {
+                wordlists: Some(policy.wordlists.clone()),
+                similarity: Some(policy.similarity),
+                warn_similarity: policy.warn_similarity,
+                skip_comments: Some(policy.skip_comments),
+                dedup: Some(policy.dedup_wordlist),
+                min_length: Some(policy.wordlist_min_length),
+                comment_prefix: Some(policy.comment_prefix.clone()),
+                match_display_threshold: Some(policy.match_display_threshold),
+                report_threshold: Some(policy.report_threshold),
+                show_match_location: Some(policy.show_match_location),
+                algorithm: Some(policy.algorithm),
+                tie_break: Some(policy.tie_break),
+                show_match_rank: Some(policy.show_match_rank),
+                fail_top: policy.fail_top,
+                stop_on_hit: Some(policy.wordlist_stop_on_hit),
+            },
+            normalized_collisions: NormalizedCollisions {
+                normalizations: Some(policy.normalizations.clone()),
+            },
+            character_share: CharacterShare {
+                max_percentage: Some(policy.max_character_share),
+            },
+            unique_chars: UniqueChars {
+                min_unique: policy.min_unique,
+            },
+            compressibility: Compressibility {
+                min_length: Some(policy.compressibility_min_length),
+                min_ratio: Some(policy.min_compression_ratio),
+            },
+            dictionary_share: DictionaryShare {
+                max_percentage: Some(policy.max_dictionary_share),
+            },
+            pattern_score: PatternScore {
+                min_log10_guesses: Some(policy.min_log10_guesses),
+            },
+            ngram_entropy: NgramEntropy {
+                enabled: Some(policy.ngram_entropy),
+                min_bits: Some(policy.min_ngram_bits),
+            },
+            word_count: WordCount {
+                min_words: Some(policy.min_words),
+            },
+            context: Context {
+                terms: Some(policy.context.clone()),
+            },
+            banned: Banned {
+                terms: Some(policy.banned.clone()),
+            },
+            default_passwords: DefaultPasswords {
+                enabled: Some(policy.default_passwords),
+                extra: Some(policy.extra_default_passwords.clone()),
+            },
+            regex: Regexes {
+                matches: Some(policy.match_regex.clone()),
+                reject: Some(policy.reject_regex.clone()),
+            },
+            account: Account {
+                username: policy.username.clone(),
+                email: policy.email.clone(),
+            },
+            substrings: Substrings {
+                enabled: Some(policy.substrings),
+                min_length: Some(policy.substring_min_length),
+            },
+            concatenated_words: ConcatenatedWords {
+                enabled: Some(policy.concatenated_words),
+            },
+            word_pairs: WordPairs {
+                enabled: Some(policy.word_pairs),
+            },
+            transformed_collisions: TransformedCollisions {
+                transformations: Some(policy.transformations.clone()),
+            },
+            leet_collisions: LeetCollisions {
+                enabled: Some(policy.leet_collisions),
+            },
+            confusables: Confusables {
+                enabled: Some(policy.confusables),
+            },
+            cracklib: Cracklib {
+                enabled: Some(policy.cracklib),
+                dictionary: policy.cracklib_dictionary.clone(),
+            },
+            breach_counts: BreachCounts {
+                wordlist: policy.breach_counts.clone(),
+                format: Some(policy.count_format),
+                threshold: Some(policy.breach_threshold),
+            },
+        }
+    }
+}
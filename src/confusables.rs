@@ -0,0 +1,48 @@
+//! Characters from other scripts that look like ASCII ones, such as the Cyrillic "а" that is
+//! indistinguishable from the Latin "a". They disguise a common password from the wordlist while
+//! looking the same when typed, or slip in through the wrong keyboard layout. A character is
+//! confusable when its skeleton from Unicode's confusables (UTS #39) is made of ASCII alone,
+//! after the compatibility forms such as the fullwidth "ｐａｓｓ" are folded to what they stand
+//! for.
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::confusable_detection;
+
+/// The ASCII characters `ch` looks like, `None` if it isn't a confusable. ASCII characters never
+/// are, even those Unicode finds confusable with one another such as "0" and "O"
+///
+/// ```
+/// use passchecker::confusables::ascii;
+///
+/// assert_eq!(ascii('а').as_deref(), Some("a"));
+/// assert_eq!(ascii('Ｐ').as_deref(), Some("P"));
+/// assert_eq!(ascii('ﬁ').as_deref(), Some("fi"));
+/// assert_eq!(ascii('a'), None);
+/// assert_eq!(ascii('é'), None);
+/// ```
+pub fn ascii(ch: char) -> Option<String> {
+    if ch.is_ascii() {
+        return None;
+    }
+    let folded: String = std::iter::once(ch).nfkc().collect();
+    let skeleton: String = confusable_detection::skeleton(&folded).collect();
+    (!skeleton.is_empty() && skeleton.is_ascii()).then_some(skeleton)
+}
+
+/// `text` with every confusable replaced by the ASCII characters it looks like
+///
+/// ```
+/// use passchecker::confusables::skeleton;
+///
+/// assert_eq!(skeleton("раssword"), "password");
+/// assert_eq!(skeleton("ｐａｓｓ\u{3000}word"), "pass word");
+/// ```
+pub fn skeleton(text: &str) -> String {
+    text.chars()
+        .map(|ch| ascii(ch).unwrap_or_else(|| ch.to_string()))
+        .collect()
+}
+
+/// How many confusables `text` contains
+pub fn count(text: &str) -> usize {
+    text.chars().filter(|&ch| ascii(ch).is_some()).count()
+}
@@ -0,0 +1,67 @@
+//! Wordlists of passwords along with how often they were seen in breaches, such as the output of
+//! `sort | uniq -c` over a breach dump. A password seen more often than a threshold fails however
+//! unusual it looks, the way services that query Pwned Passwords reject passwords by their count.
+use crate::compression;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+
+/// How the count is written on every line of a counted wordlist
+#[derive(
+    clap::ValueEnum,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum CountFormat {
+    /// The count, then a space and the password, as printed by `uniq -c`
+    #[default]
+    UniqC,
+    /// The password, then a colon and the count
+    Colon,
+    /// The password, then a tab and the count
+    Tab,
+}
+
+impl CountFormat {
+    /// The password and count on `line`, `None` if the line isn't in the format
+    ///
+    /// ```
+    /// use passchecker::counts::CountFormat;
+    ///
+    /// assert_eq!(CountFormat::UniqC.parse("  24601 hunter2"), Some(("hunter2", 24601)));
+    /// assert_eq!(CountFormat::Colon.parse("p:ss:12"), Some(("p:ss", 12)));
+    /// assert_eq!(CountFormat::Tab.parse("dragon\t7"), Some(("dragon", 7)));
+    /// assert_eq!(CountFormat::Colon.parse("dragon"), None);
+    /// ```
+    pub fn parse(self, line: &str) -> Option<(&str, u64)> {
+        let (entry, count) = match self {
+            CountFormat::UniqC => {
+                let (count, entry) = line.trim_start().split_once(' ')?;
+                (entry, count)
+            }
+            // Passwords can contain the separator, the count can't
+            CountFormat::Colon => line.rsplit_once(':')?,
+            CountFormat::Tab => line.rsplit_once('\t')?,
+        };
+        Some((entry, count.trim().parse().ok()?))
+    }
+}
+
+/// Reads the counted wordlist at `path`, decompressing it if needed, into how often every
+/// password was seen. Lines that aren't in `format` are skipped, and the counts of a password
+/// listed more than once are added up
+pub fn read(path: &Path, format: CountFormat) -> io::Result<HashMap<String, u64>> {
+    let contents = compression::read_to_string(path)?;
+    let mut counts = HashMap::new();
+    for (entry, count) in contents.lines().filter_map(|line| format.parse(line)) {
+        *counts.entry(entry.to_string()).or_default() += count;
+    }
+    Ok(counts)
+}
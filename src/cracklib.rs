@@ -0,0 +1,96 @@
+//! Reading the dictionary cracklib, the library behind `pam_cracklib` and `pam_pwquality`, checks
+//! passwords against. Distributions install it packed, as a `.pwd` file of prefix-compressed
+//! words next to its `.pwi` index, built from plain word files that are often installed too.
+use crate::compression;
+use std::ffi::OsString;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Where distributions install the packed dictionary, without the `.pwd` extension, the way
+/// cracklib itself is given dictionaries
+pub const PACKED_PATHS: &[&str] = &[
+    "/var/cache/cracklib/cracklib_dict",
+    "/usr/share/cracklib/pw_dict",
+    "/usr/lib/cracklib_dict",
+];
+
+/// Where distributions install the word files the packed dictionary is built from, looked for if
+/// there is no packed dictionary
+pub const PLAIN_PATHS: &[&str] = &["/usr/share/dict/cracklib-small", "/usr/share/dict/words"];
+
+/// How many words a block of a packed dictionary holds, the first one in full and the others as
+/// the length of the prefix they share with the previous word followed by the rest of the word
+const BLOCK_WORDS: usize = 16;
+
+/// The first dictionary found in [`PACKED_PATHS`] or [`PLAIN_PATHS`]
+pub fn find() -> Option<PathBuf> {
+    PACKED_PATHS
+        .iter()
+        .map(Path::new)
+        .find(|base| packed_path(base).is_file())
+        .or_else(|| {
+            PLAIN_PATHS
+                .iter()
+                .map(Path::new)
+                .find(|path| path.is_file())
+        })
+        .map(Path::to_owned)
+}
+
+/// The `.pwd` file of the packed dictionary `base`, `base` itself if it already has the extension
+fn packed_path(base: &Path) -> PathBuf {
+    if base.extension().is_some_and(|extension| extension == "pwd") {
+        return base.to_owned();
+    }
+    let mut path = OsString::from(base);
+    path.push(".pwd");
+    path.into()
+}
+
+/// Reads the dictionary at `path` as one word per line, unpacking it if it is a packed dictionary
+/// and reading it as a plain, possibly compressed, word file otherwise
+pub fn read(path: &Path) -> io::Result<String> {
+    let packed = packed_path(path);
+    if packed.is_file() {
+        return Ok(unpack(&compression::read_bytes(&packed)?));
+    }
+    compression::read_to_string(path)
+}
+
+/// The words of the `.pwd` file of a packed dictionary, one per line
+///
+/// ```
+/// use passchecker::cracklib::unpack;
+///
+/// assert_eq!(unpack(b"pass\0\x04word\0\x08s\0\0dragon\0"), "pass\npassword\npasswords\ndragon");
+/// ```
+pub fn unpack(bytes: &[u8]) -> String {
+    let mut words = Vec::new();
+    let mut previous: Vec<u8> = Vec::new();
+    let mut rest = bytes;
+    for slot in 0.. {
+        if rest.is_empty() {
+            break;
+        }
+        let prefix = if slot % BLOCK_WORDS == 0 {
+            0
+        } else {
+            let (&prefix, suffix) = rest.split_first().expect("rest isn't empty");
+            rest = suffix;
+            usize::from(prefix)
+        };
+        let end = rest
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(rest.len());
+        let mut word = previous[..prefix.min(previous.len())].to_vec();
+        word.extend_from_slice(&rest[..end]);
+        rest = rest.get(end + 1..).unwrap_or_default();
+        // The last block is padded with empty words
+        if !word.is_empty() {
+            words.push(String::from_utf8_lossy(&word).into_owned());
+        }
+        previous = word;
+    }
+    words.join("\n")
+}
@@ -0,0 +1,48 @@
+use std::io;
+use std::path::PathBuf;
+
+/// Operational failures that keep the tests from running at all, as opposed to a test failing
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to read config file '{}': {source}", path.display())]
+    ReadConfig { path: PathBuf, source: io::Error },
+    #[error("Failed to parse config file '{}': {source}", path.display())]
+    ParseConfig {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("Invalid config file '{}': {key} must be between 0 and 100, got {value}", path.display())]
+    InvalidPercentage {
+        path: PathBuf,
+        key: &'static str,
+        value: u8,
+    },
+    #[error("Failed to parse --policy-json: {source}")]
+    ParsePolicyJson { source: serde_json::Error },
+    #[error("Invalid --policy-json: {key} must be between 0 and 100, got {value}")]
+    InvalidPolicyJson { key: &'static str, value: u8 },
+    #[error("Failed to read wordlist '{}': {source}", path.display())]
+    ReadWordlist { path: PathBuf, source: io::Error },
+    #[error("Failed to read allowlist '{}': {source}", path.display())]
+    ReadAllowlist { path: PathBuf, source: io::Error },
+    #[error("Failed to read password history '{}': {source}", path.display())]
+    ReadHistory { path: PathBuf, source: io::Error },
+    #[error("Failed to read index '{}': {source}", path.display())]
+    ReadIndex { path: PathBuf, source: io::Error },
+    #[error("Failed to read cracklib dictionary '{}': {source}", path.display())]
+    ReadCracklib { path: PathBuf, source: io::Error },
+    #[error("Failed to read counted wordlist '{}': {source}", path.display())]
+    ReadBreachCounts { path: PathBuf, source: io::Error },
+    #[error("Invalid {kind} regex '{pattern}': {source}")]
+    InvalidRegex {
+        kind: &'static str,
+        pattern: String,
+        source: regex::Error,
+    },
+    #[error("No cracklib dictionary found in the standard paths, give one with --cracklib-dict")]
+    MissingCracklib,
+    #[error("'{hash}' is not a SHA-1 hash, which is 40 hexadecimal digits")]
+    InvalidSha1 { hash: String },
+    #[error("Pwned Passwords directory '{}' is not a directory", path.display())]
+    MissingPwnedDir { path: PathBuf },
+}
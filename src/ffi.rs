@@ -0,0 +1,150 @@
+//! A C ABI over [`Checker`], enabled by the `ffi` feature. See `ffi/passchecker.h` for the C
+//! declarations.
+//!
+//! All functions check the password against the default [`Policy`]. Passwords are NUL-terminated
+//! UTF-8 strings, results are owned by the library and have to be freed with
+//! [`passchecker_results_free`]. Strings returned by the accessors live as long as the results they
+//! came from.
+use crate::{Checker, Outcome, Policy};
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+/// The results of every test, returned by [`passchecker_results`]
+pub struct PasscheckerResults {
+    accepted: bool,
+    results: Vec<(CString, Outcome, CString)>,
+}
+
+/// # Safety
+/// `password` has to be null or point to a NUL-terminated string
+unsafe fn results(password: *const c_char) -> Option<PasscheckerResults> {
+    if password.is_null() {
+        return None;
+    }
+    let password = unsafe { CStr::from_ptr(password) }.to_str().ok()?;
+    let checker = Checker::new(Policy::default()).ok()?;
+    let results = checker.check(password);
+    // Strings with interior NULs can't be handed out, they are cut at the first one
+    let c_string = |text: &str| {
+        let text = text.split('\0').next().unwrap_or_default();
+        CString::new(text).unwrap_or_default()
+    };
+    Some(PasscheckerResults {
+        accepted: checker.accepts(password, &results),
+        results: results
+            .iter()
+            .map(|result| {
+                (
+                    c_string(&result.name),
+                    result.outcome,
+                    c_string(&result.info),
+                )
+            })
+            .collect(),
+    })
+}
+
+/// Checks `password`, returning 1 if it was accepted, 0 if it wasn't and -1 if `password` is null
+/// or not valid UTF-8
+///
+/// # Safety
+/// `password` has to be null or point to a NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn passchecker_check(password: *const c_char) -> i32 {
+    match unsafe { results(password) } {
+        Some(results) => results.accepted.into(),
+        None => -1,
+    }
+}
+
+/// Checks `password` and returns the results of every test, null if `password` is null or not
+/// valid UTF-8
+///
+/// # Safety
+/// `password` has to be null or point to a NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn passchecker_results(password: *const c_char) -> *mut PasscheckerResults {
+    match unsafe { results(password) } {
+        Some(results) => Box::into_raw(Box::new(results)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// The number of tests in `results`
+///
+/// # Safety
+/// `results` has to come from [`passchecker_results`] and not have been freed
+#[no_mangle]
+pub unsafe extern "C" fn passchecker_results_len(results: *const PasscheckerResults) -> usize {
+    unsafe { results.as_ref() }.map_or(0, |results| results.results.len())
+}
+
+/// The name of test `index`, null if `index` is out of bounds
+///
+/// # Safety
+/// `results` has to come from [`passchecker_results`] and not have been freed
+#[no_mangle]
+pub unsafe extern "C" fn passchecker_result_name(
+    results: *const PasscheckerResults,
+    index: usize,
+) -> *const c_char {
+    match unsafe { results.as_ref() }.and_then(|results| results.results.get(index)) {
+        Some((name, _, _)) => name.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+/// The outcome of test `index`: 0 for a success, 1 for a warning, 2 for a failure, 3 if it was
+/// ignored, 4 for the failure of an advisory test and -1 if `index` is out of bounds
+///
+/// # Safety
+/// `results` has to come from [`passchecker_results`] and not have been freed
+#[no_mangle]
+pub unsafe extern "C" fn passchecker_result_outcome(
+    results: *const PasscheckerResults,
+    index: usize,
+) -> i32 {
+    match unsafe { results.as_ref() }.and_then(|results| results.results.get(index)) {
+        Some((_, Outcome::Success, _)) => 0,
+        Some((_, Outcome::Warning, _)) => 1,
+        Some((_, Outcome::Failure, _)) => 2,
+        Some((_, Outcome::Ignored, _)) => 3,
+        Some((_, Outcome::Advisory, _)) => 4,
+        None => -1,
+    }
+}
+
+/// The additional info of test `index`, possibly empty, null if `index` is out of bounds
+///
+/// # Safety
+/// `results` has to come from [`passchecker_results`] and not have been freed
+#[no_mangle]
+pub unsafe extern "C" fn passchecker_result_info(
+    results: *const PasscheckerResults,
+    index: usize,
+) -> *const c_char {
+    match unsafe { results.as_ref() }.and_then(|results| results.results.get(index)) {
+        Some((_, _, info)) => info.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+/// Whether the password of `results` was accepted, 1 if it was and 0 otherwise
+///
+/// # Safety
+/// `results` has to come from [`passchecker_results`] and not have been freed
+#[no_mangle]
+pub unsafe extern "C" fn passchecker_results_accepted(results: *const PasscheckerResults) -> i32 {
+    unsafe { results.as_ref() }.is_some_and(|results| results.accepted) as i32
+}
+
+/// Frees `results`, which may be null
+///
+/// # Safety
+/// `results` has to come from [`passchecker_results`] and not have been freed already
+#[no_mangle]
+pub unsafe extern "C" fn passchecker_results_free(results: *mut PasscheckerResults) {
+    if !results.is_null() {
+        drop(unsafe { Box::from_raw(results) });
+    }
+}
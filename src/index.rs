@@ -0,0 +1,152 @@
+//! An on-disk index of a wordlist, built once with `passchecker build-index` and queried with
+//! `--index` for exact matches without reading the whole wordlist
+//!
+//! The index is a single file, all integers being little-endian `u64`s:
+//!
+//! | Field   | Size          | Content                                                      |
+//! |---------|---------------|--------------------------------------------------------------|
+//! | magic   | 8 bytes       | `PCINDEX1`                                                   |
+//! | count   | 8 bytes       | the number of entries                                        |
+//! | size    | 8 bytes       | the size of the wordlist it was built from, in bytes         |
+//! | mtime   | 8 bytes       | when that wordlist was last modified, in seconds since 1970  |
+//! | length  | 8 bytes       | the length of the path of the wordlist                       |
+//! | path    | length bytes  | the path of the wordlist, as UTF-8                           |
+//! | offsets | count×8 bytes | where each entry starts, counting from the end of the table  |
+//! | entries | the rest      | the unique entries sorted bytewise, each followed by a `\n`  |
+//!
+//! A lookup is a binary search that reads two offsets and one entry per step, so it takes a few
+//! dozen small reads whatever the size of the wordlist.
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const MAGIC: &[u8; 8] = b"PCINDEX1";
+
+/// The header of an index file, read once when the index is opened
+#[derive(Debug, Clone)]
+pub struct Index {
+    pub path: PathBuf,
+    /// The number of entries in the index
+    pub count: u64,
+    /// The wordlist the index was built from
+    pub source: PathBuf,
+    size: u64,
+    mtime: u64,
+    /// Where the offset table starts in the file
+    offsets: u64,
+}
+
+/// The size and modification time of `path`, the modification time being 0 if it is unknown
+fn fingerprint(path: &Path) -> io::Result<(u64, u64)> {
+    let metadata = fs::metadata(path)?;
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |mtime| mtime.as_secs());
+    Ok((metadata.len(), mtime))
+}
+
+fn read_u64(file: &mut File) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    file.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Writes an index of `entries`, which were read from `source`, to `output`. Returns the number of
+/// unique entries indexed
+pub fn build(entries: &[&str], source: &Path, output: &Path) -> io::Result<usize> {
+    let mut entries = entries.to_vec();
+    entries.sort_unstable();
+    entries.dedup();
+    // The wordlist is found again through an absolute path wherever the index is used from
+    let source = fs::canonicalize(source)?;
+    let (size, mtime) = fingerprint(&source)?;
+    let path = source.to_string_lossy();
+    let mut file = BufWriter::new(File::create(output)?);
+    file.write_all(MAGIC)?;
+    for field in [entries.len() as u64, size, mtime, path.len() as u64] {
+        file.write_all(&field.to_le_bytes())?;
+    }
+    file.write_all(path.as_bytes())?;
+    let mut offset = 0u64;
+    for entry in &entries {
+        file.write_all(&offset.to_le_bytes())?;
+        offset += entry.len() as u64 + 1;
+    }
+    for entry in &entries {
+        file.write_all(entry.as_bytes())?;
+        file.write_all(b"\n")?;
+    }
+    file.flush()?;
+    Ok(entries.len())
+}
+
+impl Index {
+    /// Reads the header of the index at `path`, failing if it isn't an index
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut magic = [0; 8];
+        file.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a passchecker index, build one with passchecker build-index",
+            ));
+        }
+        let count = read_u64(&mut file)?;
+        let size = read_u64(&mut file)?;
+        let mtime = read_u64(&mut file)?;
+        let length = read_u64(&mut file)?;
+        let mut source = Vec::new();
+        (&mut file).take(length).read_to_end(&mut source)?;
+        let source = String::from_utf8(source)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(Self {
+            path: path.to_owned(),
+            count,
+            source: PathBuf::from(source),
+            size,
+            mtime,
+            offsets: 8 * 5 + length,
+        })
+    }
+
+    /// Whether the wordlist the index was built from changed since. An index whose wordlist was
+    /// moved or deleted is never stale, so indexes can be shipped on their own
+    pub fn is_stale(&self) -> bool {
+        fingerprint(&self.source).is_ok_and(|fingerprint| fingerprint != (self.size, self.mtime))
+    }
+
+    /// Whether `entry` is exactly one of the entries of the index
+    pub fn contains(&self, entry: &str) -> io::Result<bool> {
+        let mut file = File::open(&self.path)?;
+        let entries = self.offsets + self.count * 8;
+        let end = file.metadata()?.len();
+        // Entry `index` ends where the next one starts, the last one at the end of the file
+        let mut read_entry = |index: u64| -> io::Result<Vec<u8>> {
+            file.seek(SeekFrom::Start(self.offsets + index * 8))?;
+            let start = entries + read_u64(&mut file)?;
+            let next = if index + 1 < self.count {
+                entries + read_u64(&mut file)?
+            } else {
+                end
+            };
+            let mut bytes = vec![0; next.saturating_sub(start + 1) as usize];
+            file.seek(SeekFrom::Start(start))?;
+            file.read_exact(&mut bytes)?;
+            Ok(bytes)
+        };
+        let (mut low, mut high) = (0, self.count);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match read_entry(middle)?.as_slice().cmp(entry.as_bytes()) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return Ok(true),
+            }
+        }
+        Ok(false)
+    }
+}
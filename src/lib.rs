@@ -0,0 +1,3260 @@
+//! The password checks behind the `passchecker` binary, usable as a library
+use charclass::ClassCounts;
+#[cfg(feature = "embedded-wordlist")]
+use const_format::{str_replace, str_split};
+use regex::Regex;
+use sha1::{Digest, Sha1};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::read_to_string,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+pub mod charclass;
+pub mod compression;
+pub mod config;
+pub mod confusables;
+pub mod counts;
+pub mod cracklib;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod index;
+pub mod lint;
+pub mod ngram;
+pub mod pattern;
+pub mod personal;
+pub mod report;
+pub mod service;
+pub mod similarity;
+
+pub use config::{Config, Policy};
+pub use error::Error;
+pub use similarity::Algorithm;
+
+/// A source of wordlist entries the tests compare passwords against, ordered from the most common
+/// entry. Only [`Wordlist::iter_entries`] has to be implemented, so any backend can be plugged
+/// into [`Checker::wordlists`]: a table of a database, a set kept in memory, or a service. The
+/// files given with [`Policy::wordlists`] are [`ExternalWordlist`]s and the embedded wordlist is
+/// an [`InternalWordlist`]
+///
+/// ```
+/// use passchecker::{Checker, Policy, Wordlist};
+/// use std::sync::Arc;
+///
+/// #[derive(Debug)]
+/// struct Pets(Vec<String>);
+///
+/// impl Wordlist for Pets {
+///     fn iter_entries(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+///         Box::new(self.0.iter().map(String::as_str))
+///     }
+/// }
+///
+/// let pets = Pets(vec!["fluffy".to_string(), "rex".to_string()]);
+/// assert!(pets.contains_exact("rex"));
+/// let mut checker = Checker::new(Policy::default()).unwrap();
+/// checker.wordlists.push(Arc::new(pets));
+/// let collides = |password| {
+///     checker
+///         .check(password)
+///         .iter()
+///         .any(|result| result.name == "collisions in wordlist" && !result.outcome.passed())
+/// };
+/// assert!(collides("fluffy"));
+/// assert!(!collides("qzvxkwjmtrplbhdn"));
+/// ```
+pub trait Wordlist: std::fmt::Debug + Send + Sync {
+    /// Every entry of the wordlist, in order
+    fn iter_entries(&self) -> Box<dyn Iterator<Item = &str> + '_>;
+
+    /// Whether `entry` is one of the entries of the wordlist. Goes over every entry unless the
+    /// backend can look entries up directly
+    fn contains_exact(&self, entry: &str) -> bool {
+        self.iter_entries().any(|candidate| candidate == entry)
+    }
+
+    /// The entries of the wordlist, one per line
+    fn entries(&self) -> Vec<&str> {
+        self.iter_entries().collect()
+    }
+
+    /// The entries of the wordlist along with the line they are on, starting from 1
+    fn numbered_entries(&self) -> Vec<(usize, &str)> {
+        (1..).zip(self.iter_entries()).collect()
+    }
+
+    /// The entries of the wordlist that should be compared against, after skipping comments and
+    /// blank lines if the policy asks for it, and entries shorter than
+    /// [`Policy::wordlist_min_length`]
+    fn filtered_entries(&self, policy: &Policy) -> Vec<&str> {
+        self.filtered_numbered_entries(policy)
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .collect()
+    }
+
+    /// [`Wordlist::filtered_entries`] along with the line each entry is on
+    fn filtered_numbered_entries(&self, policy: &Policy) -> Vec<(usize, &str)> {
+        let mut entries = self.numbered_entries();
+        if policy.skip_comments {
+            entries.retain(|(_, entry)| {
+                !entry.trim().is_empty() && !entry.starts_with(&policy.comment_prefix)
+            });
+        }
+        let min_length = usize::from(policy.wordlist_min_length);
+        if min_length > 0 {
+            entries.retain(|(_, entry)| entry.chars().count() >= min_length);
+        }
+        entries
+    }
+}
+
+/// A wordlist compiled into the binary, as its lines
+#[derive(Debug, Clone, Copy)]
+pub struct InternalWordlist(pub &'static [&'static str]);
+
+#[cfg(feature = "embedded-wordlist")]
+pub static WORDLIST: InternalWordlist = InternalWordlist(&str_split!(
+    str_replace!(include_str!("../wordlist"), '\r', ""),
+    '\n'
+));
+
+impl Wordlist for InternalWordlist {
+    fn iter_entries(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(self.0.iter().copied().filter(|line| !line.is_empty()))
+    }
+
+    fn numbered_entries(&self) -> Vec<(usize, &str)> {
+        // Splitting the embedded file leaves an empty entry after the final newline
+        (1..)
+            .zip(self.0.iter().copied())
+            .filter(|(_, line)| !line.is_empty())
+            .collect()
+    }
+}
+
+/// A wordlist read from a file, as its contents
+#[derive(Debug, Clone)]
+pub struct ExternalWordlist(pub String);
+
+impl Wordlist for ExternalWordlist {
+    fn iter_entries(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        Box::new(self.0.lines())
+    }
+}
+
+#[derive(
+    clap::ValueEnum,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    Debug,
+    PartialEq,
+    PartialOrd,
+    Eq,
+    Ord,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Ignore {
+    MinimumChars,
+    MaximumChars,
+    Numbers,
+    SpecialChars,
+    WordlistCollisions,
+    NormalizedCollisions,
+    RepeatedPatterns,
+    CharacterShare,
+    UniqueChars,
+    Compressibility,
+    DictionaryShare,
+    PatternScore,
+    PersonalData,
+    WordCount,
+    Context,
+    Username,
+    Substrings,
+    ConcatenatedWords,
+    WordPairs,
+    TransformedCollisions,
+    LeetCollisions,
+    Confusables,
+    Cracklib,
+    Pwned,
+    Index,
+    History,
+    NonAscii,
+    Service,
+    Banned,
+    BreachCount,
+    DefaultPassword,
+    MatchRegex,
+    RejectRegex,
+    NgramEntropy,
+}
+
+impl Ignore {
+    /// The name of the test as accepted by `--ignore`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Ignore::MinimumChars => "minimum-chars",
+            Ignore::MaximumChars => "maximum-chars",
+            Ignore::Numbers => "numbers",
+            Ignore::SpecialChars => "special-chars",
+            Ignore::WordlistCollisions => "wordlist-collisions",
+            Ignore::NormalizedCollisions => "normalized-collisions",
+            Ignore::RepeatedPatterns => "repeated-patterns",
+            Ignore::CharacterShare => "character-share",
+            Ignore::UniqueChars => "unique-chars",
+            Ignore::Compressibility => "compressibility",
+            Ignore::DictionaryShare => "dictionary-share",
+            Ignore::PatternScore => "pattern-score",
+            Ignore::PersonalData => "personal-data",
+            Ignore::WordCount => "word-count",
+            Ignore::Context => "context",
+            Ignore::Username => "username",
+            Ignore::Substrings => "substrings",
+            Ignore::ConcatenatedWords => "concatenated-words",
+            Ignore::WordPairs => "word-pairs",
+            Ignore::TransformedCollisions => "transformed-collisions",
+            Ignore::LeetCollisions => "leet-collisions",
+            Ignore::Confusables => "confusables",
+            Ignore::Cracklib => "cracklib",
+            Ignore::Pwned => "pwned",
+            Ignore::Index => "index",
+            Ignore::History => "history",
+            Ignore::NonAscii => "non-ascii",
+            Ignore::Service => "service",
+            Ignore::Banned => "banned",
+            Ignore::BreachCount => "breach-count",
+            Ignore::DefaultPassword => "default-password",
+            Ignore::MatchRegex => "match-regex",
+            Ignore::RejectRegex => "reject-regex",
+            Ignore::NgramEntropy => "ngram-entropy",
+        }
+    }
+
+    /// A rough rank of how long the test takes: 0 for the ones that only look at the password, 1
+    /// for the ones that read a file or a few entries, 2 for the ones that go over the wordlists.
+    /// Cheaper tests run first when there is a time budget
+    pub fn cost(&self) -> u8 {
+        match self {
+            Ignore::Pwned | Ignore::Index | Ignore::History | Ignore::Context => 1,
+            Ignore::WordlistCollisions
+            | Ignore::NormalizedCollisions
+            | Ignore::DictionaryShare
+            | Ignore::PatternScore
+            | Ignore::Substrings
+            | Ignore::ConcatenatedWords
+            | Ignore::WordPairs
+            | Ignore::TransformedCollisions
+            | Ignore::LeetCollisions
+            | Ignore::Confusables
+            | Ignore::Cracklib => 2,
+            _ => 0,
+        }
+    }
+
+    /// How much a failure of the test matters by default: critical for a password that is known
+    /// to attackers, high for one that is easily derived from a known one or from the user, down
+    /// to info for the rules that say little about strength. Overridden through
+    /// [`Policy::severities`]
+    pub fn severity(&self) -> Severity {
+        match self {
+            Ignore::WordlistCollisions
+            | Ignore::Cracklib
+            | Ignore::Pwned
+            | Ignore::Index
+            | Ignore::History
+            | Ignore::Banned
+            | Ignore::BreachCount
+            | Ignore::DefaultPassword => Severity::Critical,
+            Ignore::NormalizedCollisions
+            | Ignore::TransformedCollisions
+            | Ignore::LeetCollisions
+            | Ignore::Confusables
+            | Ignore::Context
+            | Ignore::Username
+            | Ignore::PersonalData => Severity::High,
+            Ignore::MinimumChars
+            | Ignore::RepeatedPatterns
+            | Ignore::Compressibility
+            | Ignore::DictionaryShare
+            | Ignore::PatternScore
+            | Ignore::NgramEntropy
+            | Ignore::WordCount
+            | Ignore::Substrings
+            | Ignore::ConcatenatedWords
+            | Ignore::WordPairs => Severity::Medium,
+            Ignore::Numbers
+            | Ignore::SpecialChars
+            | Ignore::CharacterShare
+            | Ignore::UniqueChars
+            | Ignore::Service
+            | Ignore::MatchRegex
+            | Ignore::RejectRegex => Severity::Low,
+            Ignore::MaximumChars | Ignore::NonAscii => Severity::Info,
+        }
+    }
+}
+
+/// How much the failure of a test matters, from the least
+#[derive(
+    clap::ValueEnum,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    /// The name of the severity as it is printed
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// The info of the tests skipped because the [`Policy::max_time_ms`] budget was used up
+pub const BUDGET_EXHAUSTED: &str = "Skipped, the time budget was used up";
+/// The info of the tests skipped because the password contains one of [`Policy::banned`]
+pub const BANNED_TERM: &str = "Skipped, the password contains a banned term";
+
+/// A transformation commonly used to disguise a known password, undone by the normalized
+/// collisions test
+#[derive(
+    clap::ValueEnum, serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Normalization {
+    /// Toggling the case of some characters, "PaSsWoRd"
+    Lowercase,
+    /// Substituting look-alike digits and symbols for letters, "p@ssw0rd"
+    Leet,
+    /// Writing the password backwards, "drowssap"
+    Reverse,
+}
+
+impl Normalization {
+    /// All the transformations, in the order they are applied
+    pub const ALL: [Normalization; 3] = [
+        Normalization::Lowercase,
+        Normalization::Leet,
+        Normalization::Reverse,
+    ];
+
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            Normalization::Lowercase => text.to_lowercase(),
+            Normalization::Leet => text
+                .chars()
+                .map(|ch| match ch {
+                    '4' | '@' => 'a',
+                    '8' => 'b',
+                    '(' => 'c',
+                    '3' => 'e',
+                    '9' => 'g',
+                    '1' | '!' | '|' => 'i',
+                    '0' => 'o',
+                    '5' | '$' => 's',
+                    '7' | '+' => 't',
+                    '2' => 'z',
+                    ch => ch,
+                })
+                .collect(),
+            Normalization::Reverse => text.chars().rev().collect(),
+        }
+    }
+
+    /// How the transformation is described once undone
+    fn description(self) -> &'static str {
+        match self {
+            Normalization::Lowercase => "lowercased",
+            Normalization::Leet => "de-leeted",
+            Normalization::Reverse => "reversed",
+        }
+    }
+}
+
+/// A transformation applied to the password as a whole before comparing it against the wordlist
+/// by similarity, in the transformed collisions test. Unlike a [`Normalization`] it is its own
+/// inverse, so only the password has to be transformed and never the wordlist. Reversing the
+/// password is left to [`Normalization::Reverse`], so a reversed password is only reported by the
+/// normalized collisions test
+#[derive(
+    clap::ValueEnum, serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Transformation {
+    /// Rotating every ASCII letter 13 places through the alphabet, "cnffjbeq"
+    Rot13,
+}
+
+impl Transformation {
+    /// ```
+    /// # use passchecker::Transformation;
+    /// assert_eq!(Transformation::Rot13.apply("Password1"), "Cnffjbeq1");
+    /// ```
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            Transformation::Rot13 => text
+                .chars()
+                .map(|ch| match ch {
+                    'a'..='m' | 'A'..='M' => (ch as u8 + 13) as char,
+                    'n'..='z' | 'N'..='Z' => (ch as u8 - 13) as char,
+                    ch => ch,
+                })
+                .collect(),
+        }
+    }
+
+    /// How the transformed password is described
+    fn description(self) -> &'static str {
+        match self {
+            Transformation::Rot13 => "ROT13",
+        }
+    }
+}
+
+/// The normalizations the leet collisions test undoes
+const LEET_NORMALIZATIONS: [Normalization; 2] = [Normalization::Lowercase, Normalization::Leet];
+
+/// Applies every transformation in `normalizations` to `text`, in the order of
+/// [`Normalization::ALL`]
+pub fn normalize(text: &str, normalizations: &[Normalization]) -> String {
+    Normalization::ALL
+        .into_iter()
+        .filter(|normalization| normalizations.contains(normalization))
+        .fold(text.to_string(), |text, normalization| {
+            normalization.apply(&text)
+        })
+}
+
+/// The part of `email` before the last `@`, all of it if there is none
+///
+/// ```
+/// use passchecker::email_local_part;
+///
+/// assert_eq!(email_local_part("john.smith@example.com"), "john.smith");
+/// assert_eq!(email_local_part("johnsmith"), "johnsmith");
+/// ```
+pub fn email_local_part(email: &str) -> &str {
+    email.rsplit_once('@').map_or(email, |(local, _)| local)
+}
+
+/// The tests whose failures are worth fixing first, the ones flagging a known or guessable password
+/// come before the composition rules
+const SUGGESTION_ORDER: [Ignore; 34] = [
+    Ignore::Banned,
+    Ignore::History,
+    Ignore::Pwned,
+    Ignore::BreachCount,
+    Ignore::Index,
+    Ignore::DefaultPassword,
+    Ignore::WordlistCollisions,
+    Ignore::Cracklib,
+    Ignore::NormalizedCollisions,
+    Ignore::TransformedCollisions,
+    Ignore::LeetCollisions,
+    Ignore::Confusables,
+    Ignore::Context,
+    Ignore::Username,
+    Ignore::PersonalData,
+    Ignore::Substrings,
+    Ignore::ConcatenatedWords,
+    Ignore::WordPairs,
+    Ignore::DictionaryShare,
+    Ignore::RepeatedPatterns,
+    Ignore::Compressibility,
+    Ignore::CharacterShare,
+    Ignore::UniqueChars,
+    Ignore::PatternScore,
+    Ignore::NgramEntropy,
+    Ignore::Service,
+    Ignore::MatchRegex,
+    Ignore::RejectRegex,
+    Ignore::MinimumChars,
+    Ignore::MaximumChars,
+    Ignore::WordCount,
+    Ignore::Numbers,
+    Ignore::SpecialChars,
+    Ignore::NonAscii,
+];
+
+/// Can be changed at compile time by setting the `PASSCHECKER_DEFAULT_MIN_LENGTH` environment
+/// variable
+pub const DEFAULT_MIN_LENGTH: u8 = match option_env!("PASSCHECKER_DEFAULT_MIN_LENGTH") {
+    Some(length) => match u8::from_str_radix(length, 10) {
+        Ok(length) => length,
+        Err(_) => panic!("PASSCHECKER_DEFAULT_MIN_LENGTH must be a number between 0 and 255"),
+    },
+    None => 8,
+};
+pub const DEFAULT_SIMILARITY: u8 = 97;
+pub const DEFAULT_COMMENT_PREFIX: &str = "#";
+pub const DEFAULT_MATCH_DISPLAY_THRESHOLD: u8 = 30;
+pub const DEFAULT_MIN_WORDS: u8 = 4;
+pub const DEFAULT_SUBSTRING_MIN_LENGTH: u8 = 4;
+/// Passwords devices and services notoriously ship with or suggest, checked by the default
+/// passwords test whether or not there is a wordlist. [`Policy::extra_default_passwords`] adds to
+/// them
+pub const DEFAULT_PASSWORDS: &[&str] = &[
+    "admin",
+    "administrator",
+    "admin123",
+    "adminadmin",
+    "root",
+    "toor",
+    "changeme",
+    "changeit",
+    "default",
+    "password",
+    "passw0rd",
+    "guest",
+    "user",
+    "test",
+    "demo",
+    "letmein",
+    "welcome",
+    "master",
+    "manager",
+    "support",
+    "service",
+    "system",
+    "sysadmin",
+    "secret",
+    "cisco",
+    "ubnt",
+    "raspberry",
+    "alpine",
+    "vagrant",
+    "oracle",
+    "postgres",
+    "mysql",
+    "1234",
+    "12345",
+    "123456",
+    "0000",
+];
+/// How many times a password can have been seen in breaches before the breach count test fails it
+pub const DEFAULT_BREACH_THRESHOLD: u64 = 10_000;
+pub const DEFAULT_MAX_DICTIONARY_SHARE: u8 = 70;
+pub const DEFAULT_MAX_CHARACTER_SHARE: u8 = 40;
+/// Passwords shorter than this aren't checked for compressibility
+pub const DEFAULT_COMPRESSIBILITY_MIN_LENGTH: u8 = 20;
+/// The smallest percentage of its length a long password may compress to
+pub const DEFAULT_MIN_COMPRESSION_RATIO: u8 = 50;
+/// The shortest repeat replaced by a back-reference when estimating the compressed length
+const MIN_BACK_REFERENCE: usize = 3;
+/// Minimum lengths above this are reported as unlikely to be intended
+pub const MAX_REASONABLE_LENGTH: u8 = 128;
+/// The estimated guesses a password has to take, as a power of 10
+pub const DEFAULT_MIN_LOG10_GUESSES: u8 = 10;
+/// The entropy in bits the bigram model of [`ngram`] has to estimate for a password
+pub const DEFAULT_MIN_NGRAM_BITS: u8 = 40;
+/// The shortest wordlist entry a password is split into when looking for concatenated words
+pub const MIN_SEGMENT_LENGTH: usize = 3;
+/// Longer passwords aren't split into words, the cost grows with the length of the password times
+/// the length of the longest wordlist entry
+pub const MAX_SEGMENTED_LENGTH: usize = 64;
+/// Longer passwords aren't split into two words, every split compares both halves against the
+/// whole wordlist
+pub const MAX_WORD_PAIR_LENGTH: usize = 32;
+/// Bits of entropy per word of a passphrase, assuming words are picked at random from the
+/// 7776 words of a Diceware list
+pub const DICEWARE_WORD_ENTROPY: f64 = 12.92;
+
+/// The estimated guesses, as powers of 10, from which a password is [`Tier::Weak`],
+/// [`Tier::Fair`], [`Tier::Strong`] and [`Tier::VeryStrong`]
+pub const DEFAULT_TIERS: [u8; 4] = [3, 6, 8, 10];
+
+/// How many guesses per second an online attacker gets, against a login that throttles attempts
+pub const DEFAULT_ONLINE_RATE: u64 = 100;
+
+/// How many guesses per second an offline attacker gets, against a leaked fast hash such as SHA-1
+/// on a few GPUs
+pub const DEFAULT_OFFLINE_RATE: u64 = 10_000_000_000;
+
+/// How long guessing a password takes at a given rate, see [`Policy::crack_times`]
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct CrackTime {
+    /// The guess rate assumed
+    pub guesses_per_second: u64,
+    pub seconds: f64,
+}
+
+impl CrackTime {
+    /// The time `10^log10_guesses` guesses take at `guesses_per_second`
+    pub fn new(log10_guesses: f64, guesses_per_second: u64) -> Self {
+        let seconds = 10f64.powf(log10_guesses - (guesses_per_second.max(1) as f64).log10());
+        Self {
+            guesses_per_second,
+            seconds,
+        }
+    }
+
+    /// The time in the largest unit it reaches, rounded
+    ///
+    /// ```
+    /// use passchecker::CrackTime;
+    ///
+    /// assert_eq!(CrackTime::new(-1.0, 1).describe(), "less than a second");
+    /// assert_eq!(CrackTime::new(6.0, 100).describe(), "3 hours");
+    /// assert_eq!(CrackTime::new(20.0, 100).describe(), "centuries");
+    /// ```
+    pub fn describe(self) -> String {
+        const UNITS: [(&str, f64); 6] = [
+            ("year", 31_556_952.0),
+            ("month", 2_629_746.0),
+            ("day", 86_400.0),
+            ("hour", 3_600.0),
+            ("minute", 60.0),
+            ("second", 1.0),
+        ];
+        if self.seconds < 1.0 {
+            return "less than a second".to_string();
+        }
+        if self.seconds >= 100.0 * UNITS[0].1 {
+            return "centuries".to_string();
+        }
+        let (unit, length) = UNITS
+            .into_iter()
+            .find(|&(_, length)| self.seconds >= length)
+            .expect("At least a second");
+        let count = (self.seconds / length).round();
+        format!("{count} {unit}{}", if count == 1.0 { "" } else { "s" })
+    }
+}
+
+/// How long guessing a password takes an online and an offline attacker
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct CrackTimes {
+    pub online: CrackTime,
+    pub offline: CrackTime,
+}
+
+/// A named strength bucket a password falls in by its estimated guesses, see [`Policy::tier`]
+#[derive(
+    clap::ValueEnum,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Tier {
+    VeryWeak,
+    Weak,
+    Fair,
+    Strong,
+    VeryStrong,
+}
+
+impl Tier {
+    /// All the tiers, from the weakest
+    pub const ALL: [Tier; 5] = [
+        Tier::VeryWeak,
+        Tier::Weak,
+        Tier::Fair,
+        Tier::Strong,
+        Tier::VeryStrong,
+    ];
+
+    /// The name of the tier as it is printed
+    pub fn name(self) -> &'static str {
+        match self {
+            Tier::VeryWeak => "Very Weak",
+            Tier::Weak => "Weak",
+            Tier::Fair => "Fair",
+            Tier::Strong => "Strong",
+            Tier::VeryStrong => "Very Strong",
+        }
+    }
+}
+
+/// How a single test went
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Success,
+    /// Passed, but close to failing
+    Warning,
+    Failure,
+    /// The test was ignored or inconclusive
+    Ignored,
+    /// Failed a test marked as advisory, which is reported but doesn't count towards whether the
+    /// password is accepted
+    Advisory,
+}
+
+impl Outcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Success => "success",
+            Outcome::Warning => "warning",
+            Outcome::Failure => "failure",
+            Outcome::Ignored => "ignored",
+            Outcome::Advisory => "advisory",
+        }
+    }
+
+    /// Whether the test counts towards the passed tests, warnings do
+    pub fn passed(self) -> bool {
+        matches!(self, Outcome::Success | Outcome::Warning)
+    }
+}
+
+/// How many tests had each outcome, the counts the summary of a password is made of
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Tally {
+    /// How many tests passed, including the ones that warned
+    pub passed: usize,
+    pub warnings: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    /// How many advisory tests failed, they aren't counted in `failed`
+    pub advisory: usize,
+}
+
+impl Tally {
+    /// Counts `outcomes`
+    ///
+    /// ```
+    /// use passchecker::{Outcome, Tally};
+    ///
+    /// let tally = Tally::of([
+    ///     Outcome::Success,
+    ///     Outcome::Warning,
+    ///     Outcome::Failure,
+    ///     Outcome::Ignored,
+    ///     Outcome::Ignored,
+    ///     Outcome::Advisory,
+    /// ]);
+    /// assert_eq!((tally.passed, tally.warnings, tally.failed), (2, 1, 1));
+    /// assert_eq!((tally.ignored, tally.advisory), (2, 1));
+    /// // The ignored tests are left out of the percentage, the advisory failure isn't
+    /// assert_eq!(tally.ran(), 4);
+    /// assert_eq!(tally.percentage(), 50.0);
+    /// assert_eq!(Tally::of([Outcome::Ignored]).percentage(), 100.0);
+    /// ```
+    pub fn of(outcomes: impl IntoIterator<Item = Outcome>) -> Self {
+        let mut tally = Self::default();
+        for outcome in outcomes {
+            match outcome {
+                Outcome::Success => tally.passed += 1,
+                Outcome::Warning => {
+                    tally.passed += 1;
+                    tally.warnings += 1
+                }
+                Outcome::Failure => tally.failed += 1,
+                Outcome::Ignored => tally.ignored += 1,
+                Outcome::Advisory => tally.advisory += 1,
+            }
+        }
+        tally
+    }
+
+    /// How many tests ran, all but the ignored ones
+    pub fn ran(&self) -> usize {
+        self.passed + self.failed + self.advisory
+    }
+
+    /// The percentage of the tests that ran that passed, 100 if none ran as none failed either
+    pub fn percentage(&self) -> f32 {
+        if self.ran() == 0 {
+            100.0
+        } else {
+            self.passed as f32 / self.ran() as f32 * 100.0
+        }
+    }
+}
+
+impl From<bool> for Outcome {
+    fn from(passed: bool) -> Self {
+        if passed {
+            Outcome::Success
+        } else {
+            Outcome::Failure
+        }
+    }
+}
+
+pub type TestFn<'a> = fn(&'a Checker, &str) -> (Outcome, Cow<'a, str>);
+/// A test that only looks at how many characters of each class the password contains, counted
+/// once per password for all of them
+pub type ClassTestFn<'a> = fn(&'a Checker, &ClassCounts) -> (Outcome, Cow<'a, str>);
+/// The step that fixes a failure of a test, from the checker and the password that failed it.
+/// `None` if there is nothing concrete to suggest
+pub type RemediationFn = fn(&Checker, &str) -> Option<String>;
+
+/// What a test is run against
+#[derive(Clone, Copy)]
+pub enum TestKind<'a> {
+    /// The password itself
+    Password(TestFn<'a>),
+    /// The character classes of the password, see [`ClassCounts`]
+    Classes(ClassTestFn<'a>),
+}
+
+pub struct Test<'a> {
+    pub name: String,
+    pub test: TestKind<'a>,
+    pub ignore: Ignore,
+    /// What the password should change to pass the test, [`Ignore::remediation`] by default
+    pub remediation: RemediationFn,
+}
+
+impl<'a> Test<'a> {
+    pub fn new(name: String, test: TestFn<'a>, ignore: Ignore) -> Self {
+        Self {
+            name,
+            test: TestKind::Password(test),
+            remediation: ignore.remediation(),
+            ignore,
+        }
+    }
+
+    /// A test that runs against the [`ClassCounts`] of the password rather than the password
+    pub fn classes(name: String, test: ClassTestFn<'a>, ignore: Ignore) -> Self {
+        Self {
+            name,
+            test: TestKind::Classes(test),
+            remediation: ignore.remediation(),
+            ignore,
+        }
+    }
+
+    /// Runs the test against `password`, whose characters are counted in `classes`, unless the
+    /// policy ignores it
+    pub fn run(
+        &self,
+        checker: &'a Checker,
+        password: &str,
+        classes: &ClassCounts,
+    ) -> (Outcome, Cow<'a, str>) {
+        if checker.policy.ignore.contains(&self.ignore) {
+            (
+                Outcome::Ignored,
+                Cow::Owned(format!("disabled with -i {:?}", self.ignore)),
+            )
+        } else {
+            match self.test {
+                TestKind::Password(test) => test(checker, password),
+                TestKind::Classes(test) => test(checker, classes),
+            }
+        }
+    }
+}
+
+/// The outcome of a single test
+#[derive(Debug, Clone)]
+pub struct TestResult<'a> {
+    pub name: String,
+    pub ignore: Ignore,
+    pub outcome: Outcome,
+    pub info: Cow<'a, str>,
+    /// How much a failure of the test matters, see [`Policy::severity`]
+    pub severity: Severity,
+    /// How long the test took to run
+    pub duration: Duration,
+}
+
+/// The results of the tests of a password, run as they are iterated over, see [`Checker::stream`]
+pub struct Results<'c, 'p> {
+    checker: &'c Checker,
+    password: &'p str,
+    /// The character classes of the password, counted once for every test that needs them
+    classes: ClassCounts,
+    tests: Vec<Test<'c>>,
+    /// The indices into `tests` of the tests left to run, in the order they run in
+    order: std::vec::IntoIter<usize>,
+    deadline: Option<Instant>,
+    /// Whether the banned terms test failed, which fails the password whatever the other tests say
+    banned: bool,
+}
+
+impl<'c> Results<'c, '_> {
+    /// Runs the next test, returning its index in [`Checker::tests`] along with its result
+    fn next_indexed(&mut self) -> Option<(usize, TestResult<'c>)> {
+        let index = self.order.next()?;
+        let test = &self.tests[index];
+        let start = Instant::now();
+        let (outcome, info) = if self.banned {
+            (Outcome::Ignored, Cow::Borrowed(BANNED_TERM))
+        } else if self.deadline.is_some_and(|deadline| start >= deadline) {
+            (Outcome::Ignored, Cow::Borrowed(BUDGET_EXHAUSTED))
+        } else {
+            match test.run(self.checker, self.password, &self.classes) {
+                (Outcome::Failure, info) if self.checker.policy.advisory.contains(&test.ignore) => {
+                    (Outcome::Advisory, info)
+                }
+                result => result,
+            }
+        };
+        self.banned |= test.ignore == Ignore::Banned && outcome == Outcome::Failure;
+        let result = TestResult {
+            name: test.name.clone(),
+            ignore: test.ignore.clone(),
+            outcome,
+            info,
+            severity: self.checker.policy.severity(&test.ignore),
+            duration: start.elapsed(),
+        };
+        Some((index, result))
+    }
+}
+
+impl<'c> Iterator for Results<'c, '_> {
+    type Item = TestResult<'c>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_indexed().map(|(_, result)| result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.order.size_hint()
+    }
+}
+
+/// Which of several wordlist entries equally similar to the password is reported as the best match
+#[derive(
+    clap::ValueEnum,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum TieBreak {
+    /// The shortest entry, the first one among entries of the same length
+    Shortest,
+    /// The longest entry, the first one among entries of the same length
+    Longest,
+    /// The entry that comes first in the wordlists
+    #[default]
+    FirstInFile,
+    /// The entry that comes first when sorted bytewise
+    Alphabetical,
+}
+
+impl TieBreak {
+    /// Whether `candidate` wins over `current`, an entry that came before it with the same
+    /// similarity
+    pub fn prefers(self, candidate: &str, current: &str) -> bool {
+        match self {
+            TieBreak::Shortest => candidate.chars().count() < current.chars().count(),
+            TieBreak::Longest => candidate.chars().count() > current.chars().count(),
+            TieBreak::FirstInFile => false,
+            TieBreak::Alphabetical => candidate < current,
+        }
+    }
+}
+
+/// Finds the wordlist entry most similar to `password` along with its similarity in the 0-1 range,
+/// `None` if there are no entries. The similarity is computed with `algorithm`, ties are broken by
+/// `tie_break`
+///
+/// ```
+/// # use passchecker::{best_match, Algorithm, TieBreak};
+/// // Both entries are 75% similar to the password
+/// let entries = ["abcz", "abcy"];
+/// let best = |tie_break| best_match("abcx", &entries, tie_break, Algorithm::Lcs).unwrap().0;
+/// assert_eq!(best(TieBreak::FirstInFile), "abcz");
+/// assert_eq!(best(TieBreak::Alphabetical), "abcy");
+/// ```
+pub fn best_match(
+    password: &str,
+    entries: &[&str],
+    tie_break: TieBreak,
+    algorithm: Algorithm,
+) -> Option<(String, f64)> {
+    best_match_index(password, entries, tie_break, algorithm)
+        .map(|(index, similarity)| (entries[index].to_string(), similarity))
+}
+
+/// Every entry of `entries` more similar to `password` than `cutoff`, in the 0-1 range, along with
+/// its similarity, from the most similar. Equally similar entries keep their order in `entries`.
+/// Only the entries above the cutoff are kept while scanning, however large `entries` is
+///
+/// ```
+/// # use passchecker::{matches_above, Algorithm};
+/// let entries = ["letmein", "password", "letmein1", "dragon"];
+/// let matches = matches_above("letmein", &entries, 0.5, Algorithm::Lcs);
+/// assert_eq!(matches, [("letmein", 1.0), ("letmein1", 0.875)]);
+/// ```
+pub fn matches_above<'e>(
+    password: &str,
+    entries: &[&'e str],
+    cutoff: f64,
+    algorithm: Algorithm,
+) -> Vec<(&'e str, f64)> {
+    let mut matches: Vec<(&str, f64)> = entries
+        .iter()
+        .map(|&entry| (entry, algorithm.similarity(entry, password)))
+        .filter(|(_, similarity)| *similarity > cutoff)
+        .collect();
+    matches.sort_by(|(_, left), (_, right)| right.total_cmp(left));
+    matches
+}
+
+/// [`best_match`], giving the index of the entry in `entries` instead of the entry itself
+pub fn best_match_index(
+    password: &str,
+    entries: &[&str],
+    tie_break: TieBreak,
+    algorithm: Algorithm,
+) -> Option<(usize, f64)> {
+    let mut best: Option<(usize, f64)> = None;
+    for (index, entry) in entries.iter().enumerate() {
+        let similarity = algorithm.similarity(entry, password);
+        let better = best.is_none_or(|(best_index, best)| {
+            similarity > best
+                || (similarity == best && tie_break.prefers(entry, entries[best_index]))
+        });
+        if better {
+            best = Some((index, similarity));
+            // Nothing can beat an exact match, no need to scan the rest of the wordlist
+            if similarity >= 1.0 {
+                break;
+            }
+        }
+    }
+    best
+}
+
+/// Finds the shortest unit `text` is made of repeated at least twice along with how many times it
+/// is repeated, `None` if `text` isn't made of a repeated unit
+///
+/// ```
+/// # use passchecker::repeating_unit;
+/// assert_eq!(repeating_unit("abcabcabc"), Some(("abc", 3)));
+/// assert_eq!(repeating_unit("abcabcab"), None);
+/// ```
+pub fn repeating_unit(text: &str) -> Option<(&str, usize)> {
+    let boundaries: Vec<usize> = text.char_indices().map(|(index, _)| index).collect();
+    let chars = boundaries.len();
+    (1..=chars / 2)
+        .filter(|length| chars.is_multiple_of(*length))
+        .find_map(|length| {
+            let unit = &text[..boundaries[length]];
+            let repetitions = chars / length;
+            (unit.repeat(repetitions) == text).then_some((unit, repetitions))
+        })
+}
+
+/// A quick estimate of the length `text` compresses to, in characters: a greedy LZ77 parse in
+/// which every character that doesn't continue an earlier repeat costs 1 and every repeat of at
+/// least [`MIN_BACK_REFERENCE`] characters, which may overlap itself, costs 2
+///
+/// ```
+/// # use passchecker::compressed_length;
+/// assert_eq!(compressed_length(&"ab".repeat(25)), 4);
+/// assert_eq!(compressed_length("passwordpassword1234"), 14);
+/// assert_eq!(compressed_length("Xq7#mK9!vR2"), 11);
+/// ```
+pub fn compressed_length(text: &str) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let mut length = 0;
+    let mut position = 0;
+    while position < chars.len() {
+        let longest = (0..position)
+            .map(|start| {
+                chars[position..]
+                    .iter()
+                    .zip(&chars[start..])
+                    .take_while(|(next, earlier)| next == earlier)
+                    .count()
+            })
+            .max()
+            .unwrap_or_default();
+        if longest >= MIN_BACK_REFERENCE {
+            length += 2;
+            position += longest;
+        } else {
+            length += 1;
+            position += 1;
+        }
+    }
+    length
+}
+
+/// Splits `text` into the fewest `entries` that, concatenated, make up all of it, `None` if it
+/// can't be split that way. `longest` is the length of the longest entry in characters, entries
+/// shorter than [`MIN_SEGMENT_LENGTH`] are never used
+///
+/// ```
+/// # use passchecker::segment;
+/// # use std::collections::HashSet;
+/// let entries: HashSet<String> = ["red", "blue", "green", "bluegreen"].map(String::from).into();
+/// assert_eq!(segment("redbluegreen", &entries, 9), Some(vec!["red", "bluegreen"]));
+/// assert_eq!(segment("redbluexgreen", &entries, 9), None);
+/// ```
+pub fn segment<'a>(
+    text: &'a str,
+    entries: &HashSet<String>,
+    longest: usize,
+) -> Option<Vec<&'a str>> {
+    let boundaries: Vec<usize> = text
+        .char_indices()
+        .map(|(index, _)| index)
+        .chain([text.len()])
+        .collect();
+    let chars = boundaries.len() - 1;
+    // The fewest entries the first `end` characters split into, along with where the last one
+    // starts
+    let mut best: Vec<Option<(usize, usize)>> = vec![None; chars + 1];
+    best[0] = Some((0, 0));
+    for end in MIN_SEGMENT_LENGTH..=chars {
+        for start in end.saturating_sub(longest)..=end - MIN_SEGMENT_LENGTH {
+            let Some((count, _)) = best[start] else {
+                continue;
+            };
+            if best[end].is_some_and(|(best, _)| best <= count + 1) {
+                continue;
+            }
+            if entries.contains(&text[boundaries[start]..boundaries[end]]) {
+                best[end] = Some((count + 1, start));
+            }
+        }
+    }
+    best[chars]?;
+    let mut words = Vec::new();
+    let mut end = chars;
+    while end > 0 {
+        let (_, start) = best[end]?;
+        words.push(&text[boundaries[start]..boundaries[end]]);
+        end = start;
+    }
+    words.reverse();
+    Some(words)
+}
+
+/// Formats a similarity in the 0-1 range as a percentage rounded to one decimal place
+///
+/// ```
+/// use passchecker::percentage;
+///
+/// assert_eq!(percentage(0.97000000001), "97.0%");
+/// assert_eq!(percentage(1.0), "100.0%");
+/// assert_eq!(percentage(0.123456), "12.3%");
+/// ```
+pub fn percentage(similarity: f64) -> String {
+    format!("{:.1}%", similarity * 100.0)
+}
+
+/// Why the wordlist tests are inconclusive when there is no wordlist to check against
+const NO_WORDLIST: &str = "No wordlist provided and this build of passchecker has no internal wordlist, provide one with --wordlist";
+
+/// Why the collisions test is inconclusive when the wordlists have no entries, such as an empty
+/// STDIN given with `--wordlist -`
+const EMPTY_WORDLIST: &str = "The wordlist has no entries to compare the password against";
+
+/// The info of the tests that need the plaintext password, skipped by [`Checker::check_sha1`]
+pub const NEEDS_PLAINTEXT: &str = "Skipped, only the SHA-1 hash of the password was given";
+
+/// `hex` as an uppercase SHA-1 hash, if it is one: 40 hexadecimal digits in either case
+///
+/// ```
+/// use passchecker::parse_sha1;
+///
+/// let hash = "5baa61e4c9b93f3f0682250b6cf8331b7ee68fd8";
+/// assert_eq!(parse_sha1(hash).unwrap(), hash.to_uppercase());
+/// assert!(parse_sha1("5baa61e4").is_err());
+/// assert!(parse_sha1(&"g".repeat(40)).is_err());
+/// ```
+pub fn parse_sha1(hex: &str) -> Result<String, Error> {
+    let hex = hex.trim();
+    if hex.len() != 40 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(Error::InvalidSha1 {
+            hash: hex.to_string(),
+        });
+    }
+    Ok(hex.to_ascii_uppercase())
+}
+
+/// The SHA-1 hash of `text` in uppercase hexadecimal, as used by Pwned Passwords
+pub fn sha1_hex(text: &str) -> String {
+    Sha1::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect()
+}
+
+/// The outcome of the breached passwords test for the uppercase SHA-1 `hash`, looked up in the
+/// range files of `dir`
+fn breached(dir: &Path, hash: &str) -> (Outcome, Cow<'static, str>) {
+    let (prefix, suffix) = hash.split_at(5);
+    // The official downloader names the range files after the prefix, with a .txt
+    // extension
+    let Some(range) = [dir.join(format!("{prefix}.txt")), dir.join(prefix)]
+        .iter()
+        .find_map(|path| read_to_string(path).ok())
+    else {
+        return (
+            Outcome::Ignored,
+            Cow::Owned(format!(
+                "No range file for the hash prefix {prefix} in {}",
+                dir.display()
+            )),
+        );
+    };
+    // Every line of a range file is the rest of a hash and how often it was seen
+    let count = range.lines().find_map(|line| {
+        let (entry, count) = line.trim().split_once(':')?;
+        entry
+            .eq_ignore_ascii_case(suffix)
+            .then(|| count.trim().to_string())
+    });
+    match count {
+        Some(count) => (
+            Outcome::Failure,
+            Cow::Owned(format!("Seen {count} times in Pwned Passwords breaches")),
+        ),
+        None => (Outcome::Success, Cow::Borrowed("")),
+    }
+}
+
+/// The default password `password` is, or is close to, along with whether it is the default
+/// itself once lowercased and de-leeted. Close means at least as similar as
+/// [`Policy::similarity_threshold`], or the default with only digits and symbols added at the end
+fn default_password<'c>(checker: &'c Checker, password: &str) -> Option<(&'c str, bool)> {
+    let policy = &checker.policy;
+    let normalized = normalize(password, &LEET_NORMALIZATIONS);
+    let lowercase = password.to_lowercase();
+    let stem =
+        lowercase.trim_end_matches(|ch: char| ch.is_ascii_digit() || ch.is_ascii_punctuation());
+    let defaults = DEFAULT_PASSWORDS
+        .iter()
+        .copied()
+        .chain(policy.extra_default_passwords.iter().map(String::as_str))
+        .filter(|default| !default.is_empty());
+    let mut near = None;
+    for default in defaults {
+        let normalized_default = normalize(default, &LEET_NORMALIZATIONS);
+        if normalized == normalized_default {
+            return Some((default, true));
+        }
+        if near.is_none()
+            && (stem == default.to_lowercase()
+                || policy
+                    .algorithm
+                    .similarity(&normalized_default, &normalized)
+                    >= policy.similarity_threshold())
+        {
+            near = Some((default, false));
+        }
+    }
+    near
+}
+
+/// The outcome of the previous passwords test when only the uppercase SHA-1 `hash` of the
+/// password is known: plain entries are hashed in turn, and nothing can be matched by similarity
+fn previous_hash(history: &str, hash: &str) -> (Outcome, Cow<'static, str>) {
+    for (index, entry) in history.lines().enumerate() {
+        let entry_hash = match entry.strip_prefix("sha1:") {
+            Some(entry_hash) => entry_hash.trim().to_string(),
+            None if entry.is_empty() => continue,
+            None => sha1_hex(entry),
+        };
+        if entry_hash.eq_ignore_ascii_case(hash) {
+            let line = index + 1;
+            return (
+                Outcome::Failure,
+                Cow::Owned(format!("Password is the previous password on line {line}")),
+            );
+        }
+    }
+    (Outcome::Success, Cow::Borrowed(""))
+}
+
+/// The lines of `text` without the ones repeating an earlier line, in their original order, along
+/// with how many were removed
+///
+/// ```
+/// use passchecker::dedup;
+///
+/// assert_eq!(dedup("123456\npassword\n123456\nqwerty\npassword\n"), ("123456\npassword\nqwerty".to_string(), 2));
+/// ```
+pub fn dedup(text: &str) -> (String, usize) {
+    let mut seen = HashSet::new();
+    let mut removed = 0;
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| {
+            let first = seen.insert(*line);
+            removed += usize::from(!first);
+            first
+        })
+        .collect();
+    (lines.join("\n"), removed)
+}
+
+/// Hides all but the first and last characters of `text` behind asterisks, texts of 2 characters
+/// or less are hidden entirely
+pub fn mask(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    match chars.as_slice() {
+        [first, middle @ .., last] if !middle.is_empty() => {
+            format!("{first}{}{last}", "*".repeat(middle.len()))
+        }
+        _ => "*".repeat(chars.len()),
+    }
+}
+
+/// Compares two byte strings in time that only depends on their lengths
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Where a wordlist entry is: the index of its wordlist, the line it is on and its rank, its
+/// position among the entries of the wordlist starting from 1
+type Location = (usize, usize, usize);
+
+/// A policy along with the files it points at, loaded once so any number of passwords can be
+/// checked against it
+#[derive(Debug, Clone)]
+pub struct Checker {
+    pub policy: Policy,
+    /// The wordlists from [`Policy::wordlists`], in the same order, followed by any a library
+    /// user added before the first check. Empty if the internal wordlist should be used
+    pub wordlists: Vec<Arc<dyn Wordlist>>,
+    pub allowlist: Option<String>,
+    pub history: Option<String>,
+    /// The header of the index from [`Policy::index`]
+    pub index: Option<index::Index>,
+    /// The cracklib dictionary enabled by [`Policy::cracklib`] and where it was read from
+    pub cracklib: Option<(PathBuf, ExternalWordlist)>,
+    /// How often every password of the counted wordlist from [`Policy::breach_counts`] was seen,
+    /// and where it was read from
+    pub breach_counts: Option<(PathBuf, HashMap<String, u64>)>,
+    /// The compiled [`Policy::match_regex`], every one of which the password has to match
+    pub match_regexes: Vec<Regex>,
+    /// The compiled [`Policy::reject_regex`], none of which the password may match
+    pub reject_regexes: Vec<Regex>,
+    /// Whether parts of the password echoed in the infos are masked with [`mask`]
+    pub mask: bool,
+    /// How many repeated lines were removed from the wordlists, with [`Policy::dedup_wordlist`]
+    pub duplicates_removed: usize,
+    /// How many wordlist entries are skipped for being shorter than
+    /// [`Policy::wordlist_min_length`]
+    pub short_entries: usize,
+    /// Every wordlist entry along with where it first appears, as the wordlist index, line and
+    /// rank, and the number of entries. Built on the first check that looks for exact collisions,
+    /// see [`Policy::exact_collisions`]
+    exact_entries: OnceLock<(HashMap<String, Location>, usize)>,
+    /// Every wordlist entry lowercased and de-leeted, along with the first entry that normalizes
+    /// to it. Built on the first check of the leet collisions test
+    leet_entries: OnceLock<HashMap<String, String>>,
+    /// Every wordlist entry lowercased, along with its rank, for [`pattern::decompose`]. Built on
+    /// the first estimate of the guesses a password takes
+    ranks: OnceLock<HashMap<String, usize>>,
+}
+
+impl Checker {
+    /// Reads the wordlists and allowlist the policy points at, a wordlist named `-` is read from
+    /// STDIN. Wordlists are decompressed if they are compressed
+    pub fn new(policy: Policy) -> Result<Self, Error> {
+        Self::with_progress(policy, |_, _| ())
+    }
+
+    /// [`Checker::new`], calling `report` with the path of the wordlist being read and how far
+    /// reading it got as it goes, to show the progress of reading large wordlists
+    pub fn with_progress(
+        policy: Policy,
+        mut report: impl FnMut(&Path, compression::Progress),
+    ) -> Result<Self, Error> {
+        // Before the wordlists are read, so a typo in a pattern is reported right away
+        let compile = |patterns: &[String], kind| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Regex::new(pattern).map_err(|source| Error::InvalidRegex {
+                        kind,
+                        pattern: pattern.clone(),
+                        source,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let match_regexes = compile(&policy.match_regex, "required format")?;
+        let reject_regexes = compile(&policy.reject_regex, "rejected format")?;
+        let mut wordlists: Vec<Arc<dyn Wordlist>> = Vec::with_capacity(policy.wordlists.len());
+        let mut duplicates_removed = 0;
+        for path in &policy.wordlists {
+            let mut report = |progress| report(path, progress);
+            // STDIN is read in full like a file rather than streamed, most tests go over the
+            // entries more than once
+            let wordlist = if path == Path::new("-") {
+                compression::read_stream_with_progress(
+                    std::io::stdin().lock(),
+                    None,
+                    None,
+                    &mut report,
+                )
+            } else {
+                std::fs::File::open(path).and_then(|file| {
+                    let size = file.metadata()?.len();
+                    compression::read_stream_with_progress(
+                        std::io::BufReader::new(file),
+                        compression::Compression::from_extension(path),
+                        Some(size),
+                        &mut report,
+                    )
+                })
+            };
+            let mut wordlist = wordlist.map_err(|source| Error::ReadWordlist {
+                path: path.clone(),
+                source,
+            })?;
+            if policy.dedup_wordlist {
+                let removed;
+                (wordlist, removed) = dedup(&wordlist);
+                duplicates_removed += removed;
+            }
+            wordlists.push(Arc::new(ExternalWordlist(wordlist)));
+        }
+        if let Some(ref path) = policy.pwned_dir {
+            if !path.is_dir() {
+                return Err(Error::MissingPwnedDir { path: path.clone() });
+            }
+        }
+        let allowlist = match policy.allowlist {
+            Some(ref path) => {
+                Some(read_to_string(path).map_err(|source| Error::ReadAllowlist {
+                    path: path.clone(),
+                    source,
+                })?)
+            }
+            None => None,
+        };
+        let history = match policy.history {
+            Some(ref path) => Some(read_to_string(path).map_err(|source| Error::ReadHistory {
+                path: path.clone(),
+                source,
+            })?),
+            None => None,
+        };
+        let index = match policy.index {
+            Some(ref path) => {
+                Some(index::Index::open(path).map_err(|source| Error::ReadIndex {
+                    path: path.clone(),
+                    source,
+                })?)
+            }
+            None => None,
+        };
+        let cracklib = if policy.cracklib {
+            let path = match policy.cracklib_dictionary {
+                Some(ref path) => path.clone(),
+                None => cracklib::find().ok_or(Error::MissingCracklib)?,
+            };
+            let words = cracklib::read(&path).map_err(|source| Error::ReadCracklib {
+                path: path.clone(),
+                source,
+            })?;
+            Some((path, ExternalWordlist(words)))
+        } else {
+            None
+        };
+        let breach_counts = match policy.breach_counts {
+            Some(ref path) => {
+                let counts = counts::read(path, policy.count_format).map_err(|source| {
+                    Error::ReadBreachCounts {
+                        path: path.clone(),
+                        source,
+                    }
+                })?;
+                Some((path.clone(), counts))
+            }
+            None => None,
+        };
+        let mut checker = Self {
+            policy,
+            wordlists,
+            allowlist,
+            history,
+            index,
+            cracklib,
+            breach_counts,
+            match_regexes,
+            reject_regexes,
+            mask: false,
+            duplicates_removed,
+            short_entries: 0,
+            exact_entries: OnceLock::new(),
+            leet_entries: OnceLock::new(),
+            ranks: OnceLock::new(),
+        };
+        if checker.policy.wordlist_min_length > 0 {
+            let unfiltered = Policy {
+                wordlist_min_length: 0,
+                ..checker.policy.clone()
+            };
+            checker.short_entries = checker
+                .active_wordlists()
+                .iter()
+                .map(|wordlist| {
+                    wordlist.filtered_entries(&unfiltered).len()
+                        - wordlist.filtered_entries(&checker.policy).len()
+                })
+                .sum();
+        }
+        Ok(checker)
+    }
+
+    /// The entry of all active wordlists most similar to `text`, along with its similarity, only
+    /// looking for identical entries when [`Policy::exact_collisions`] allows it
+    fn best_wordlist_match(&self, text: &str) -> Option<(String, f64)> {
+        let policy = &self.policy;
+        if policy.exact_collisions() {
+            return self.exact_location(text).0.map(|_| (text.to_string(), 1.0));
+        }
+        self.active_wordlists()
+            .iter()
+            .filter_map(|wordlist| {
+                best_match(
+                    text,
+                    &wordlist.filtered_entries(policy),
+                    policy.tie_break,
+                    policy.algorithm,
+                )
+            })
+            .reduce(|best, next| if next.1 > best.1 { next } else { best })
+    }
+
+    /// Where the first wordlist entry identical to `password` is, as the wordlist index, line and
+    /// rank, along with the number of entries in all wordlists. The entries are put in a map on
+    /// the first lookup, so every lookup after that takes constant time
+    fn exact_location(&self, password: &str) -> (Option<Location>, usize) {
+        let (entries, count) = self.exact_entries.get_or_init(|| {
+            let mut entries = HashMap::new();
+            let mut count = 0;
+            for (wordlist_index, wordlist) in self.active_wordlists().iter().enumerate() {
+                let numbered_entries = wordlist.filtered_numbered_entries(&self.policy);
+                count += numbered_entries.len();
+                for (rank, (line, entry)) in (1..).zip(numbered_entries) {
+                    entries
+                        .entry(entry.to_string())
+                        .or_insert((wordlist_index, line, rank));
+                }
+            }
+            (entries, count)
+        });
+        (entries.get(password).copied(), *count)
+    }
+
+    /// The first wordlist entry that is the same as `normalized` once lowercased and de-leeted.
+    /// The normalized entries are put in a map on the first lookup, like in
+    /// [`Checker::exact_location`]
+    fn leet_entry(&self, normalized: &str) -> Option<&str> {
+        let entries = self.leet_entries.get_or_init(|| {
+            let mut entries = HashMap::new();
+            for wordlist in self.active_wordlists() {
+                for entry in wordlist.filtered_entries(&self.policy) {
+                    entries
+                        .entry(normalize(entry, &LEET_NORMALIZATIONS))
+                        .or_insert_with(|| entry.to_string());
+                }
+            }
+            entries
+        });
+        entries.get(normalized).map(String::as_str)
+    }
+
+    /// The wordlists the tests check against, the internal wordlist if none were provided. Empty
+    /// if none were provided and the `embedded-wordlist` feature is disabled
+    pub fn active_wordlists(&self) -> Vec<&dyn Wordlist> {
+        #[cfg(feature = "embedded-wordlist")]
+        if self.wordlists.is_empty() {
+            return vec![&WORDLIST];
+        }
+        self.wordlists.iter().map(|wordlist| &**wordlist).collect()
+    }
+
+    /// `text`, masked if [`Checker::mask`] is set. Used for anything in the infos that may be part
+    /// of the password, such as wordlist matches
+    pub fn shown<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        if self.mask {
+            Cow::Owned(mask(text))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    /// Whether `password` is exactly one of the lines of the allowlist, every line is compared in
+    /// constant time so the position of the match isn't leaked
+    pub fn is_allowlisted(&self, password: &str) -> bool {
+        let Some(ref allowlist) = self.allowlist else {
+            return false;
+        };
+        allowlist.lines().fold(false, |found, entry| {
+            found | constant_time_eq(entry.as_bytes(), password.as_bytes())
+        })
+    }
+
+    /// Splits `password` into the chunks that take the fewest guesses, ranking wordlist entries by
+    /// their position in the wordlists
+    pub fn decompose(&self, password: &str) -> Vec<pattern::Chunk> {
+        let ranks = self.ranks.get_or_init(|| {
+            // Earlier entries are more common, so they are guessed first
+            let mut ranks = HashMap::new();
+            for wordlist in self.active_wordlists() {
+                for (rank, entry) in (1..).zip(wordlist.filtered_entries(&self.policy)) {
+                    ranks.entry(entry.to_lowercase()).or_insert(rank);
+                }
+            }
+            ranks
+        });
+        pattern::decompose(password, ranks)
+    }
+
+    /// The estimated guesses `password` takes, as a power of 10, and the strength tier they put it
+    /// in
+    ///
+    /// ```
+    /// use passchecker::{Checker, Policy, Tier};
+    ///
+    /// let policy = Policy { wordlists: vec!["tests/wordlist.txt".into()], ..Policy::default() };
+    /// let checker = Checker::new(policy).unwrap();
+    /// assert_eq!(checker.strength("password").1, Tier::VeryWeak);
+    /// assert_eq!(checker.strength("Xq7#mK9!vR2$wL").1, Tier::VeryStrong);
+    /// ```
+    pub fn strength(&self, password: &str) -> (f64, Tier) {
+        let log10_guesses: f64 = self
+            .decompose(password)
+            .iter()
+            .map(|chunk| chunk.log10_guesses)
+            .sum();
+        (log10_guesses, self.policy.tier(log10_guesses))
+    }
+
+    /// Whether `password`, whose tests gave `results`, is accepted: by its tier alone if there is a
+    /// [`Policy::min_tier`], by [`Policy::accepts`] otherwise
+    ///
+    /// ```
+    /// use passchecker::{Checker, Policy, Tier};
+    ///
+    /// let policy = Policy {
+    ///     min_tier: Some(Tier::Strong),
+    ///     wordlists: vec!["tests/wordlist.txt".into()],
+    ///     ..Policy::default()
+    /// };
+    /// let checker = Checker::new(policy).unwrap();
+    /// // Without numbers or symbols, but hard enough to guess
+    /// let password = "qzvxkwjmtrplbhdn";
+    /// let results = checker.check(password);
+    /// assert!(!checker.policy.accepts(&results));
+    /// assert!(checker.accepts(password, &results));
+    /// assert!(!checker.accepts("password", &checker.check("password")));
+    /// ```
+    pub fn accepts(&self, password: &str, results: &[TestResult]) -> bool {
+        match self.policy.min_tier {
+            Some(min_tier) => self.strength(password).1 >= min_tier,
+            None => self.policy.accepts(results),
+        }
+    }
+
+    /// The results of a password no test ran on, such as an empty one, every test being ignored
+    /// with `reason` as its info
+    pub fn skipped(&self, reason: &'static str) -> Vec<TestResult<'_>> {
+        self.tests()
+            .into_iter()
+            .map(|test| TestResult {
+                severity: self.policy.severity(&test.ignore),
+                name: test.name,
+                ignore: test.ignore,
+                outcome: Outcome::Ignored,
+                info: Cow::Borrowed(reason),
+                duration: Duration::ZERO,
+            })
+            .collect()
+    }
+
+    /// All the tests, in the order they are run. Their names are plain text, coloring them is
+    /// left to the caller
+    ///
+    /// ```
+    /// use passchecker::{Checker, Policy};
+    ///
+    /// let checker = Checker::new(Policy::default()).unwrap();
+    /// assert_eq!(checker.tests()[0].name, "At least 8 characters");
+    /// ```
+    pub fn tests(&self) -> Vec<Test<'_>> {
+        tests(&self.policy)
+    }
+
+    /// The remediation of every test that failed among `results`, the most important first by
+    /// [`SUGGESTION_ORDER`], without repeating the steps several tests share
+    ///
+    /// ```
+    /// use passchecker::{Checker, Policy};
+    ///
+    /// let checker = Checker::new(Policy { min_length: 12, ..Policy::default() }).unwrap();
+    /// let steps = checker.checklist("qzvxkwjm", &checker.check("qzvxkwjm"));
+    /// assert_eq!(steps[..2], ["Add 4 more characters", "Add a number"]);
+    /// ```
+    pub fn checklist(&self, password: &str, results: &[TestResult]) -> Vec<String> {
+        let mut steps = Vec::new();
+        for step in remediations(self, password, results) {
+            if !steps.contains(&step) {
+                steps.push(step);
+            }
+        }
+        steps
+    }
+
+    /// Runs every test from [`Checker::tests`] against `password`
+    ///
+    /// With [`Policy::max_time_ms`] the cheapest tests run first, and the tests that haven't
+    /// started once the budget is used up are ignored with [`BUDGET_EXHAUSTED`] as their info. A
+    /// test that already started always runs to completion. The results are in the order of
+    /// [`Checker::tests`] either way
+    pub fn check(&self, password: &str) -> Vec<TestResult<'_>> {
+        let mut stream = self.stream(password);
+        let mut results: Vec<Option<TestResult>> = stream.tests.iter().map(|_| None).collect();
+        while let Some((index, result)) = stream.next_indexed() {
+            results[index] = Some(result);
+        }
+        results.into_iter().flatten().collect()
+    }
+
+    /// Runs the tests that work from the SHA-1 hash of a password alone, from the uppercase `hash`
+    /// returned by [`parse_sha1`]: the breached passwords test, and the previous passwords test
+    /// matching entries exactly. Every other test is ignored with [`NEEDS_PLAINTEXT`] as its info
+    pub fn check_sha1(&self, hash: &str) -> Vec<TestResult<'_>> {
+        let policy = &self.policy;
+        self.tests()
+            .into_iter()
+            .map(|test| {
+                let start = Instant::now();
+                let (outcome, info) = match (&test.ignore, &policy.pwned_dir, &self.history) {
+                    (ignore, _, _) if policy.ignore.contains(ignore) => {
+                        test.run(self, "", &ClassCounts::default())
+                    }
+                    (Ignore::Pwned, Some(dir), _) => breached(dir, hash),
+                    (Ignore::History, _, Some(history)) => previous_hash(history, hash),
+                    _ => (Outcome::Ignored, Cow::Borrowed(NEEDS_PLAINTEXT)),
+                };
+                let outcome = match outcome {
+                    Outcome::Failure if policy.advisory.contains(&test.ignore) => Outcome::Advisory,
+                    outcome => outcome,
+                };
+                TestResult {
+                    name: test.name,
+                    ignore: test.ignore.clone(),
+                    outcome,
+                    info,
+                    severity: policy.severity(&test.ignore),
+                    duration: start.elapsed(),
+                }
+            })
+            .collect()
+    }
+
+    /// Runs the tests from [`Checker::tests`] against `password` one at a time, as the returned
+    /// iterator is advanced, so results can be shown as they come in and the tests left can be
+    /// skipped by dropping it
+    ///
+    /// The tests are run from the cheapest, by [`Ignore::cost`], so the wordlist scans come last,
+    /// and in the order of [`Checker::tests`] among equally cheap ones. The
+    /// [`Policy::max_time_ms`] budget starts when the iterator is created, time spent between
+    /// calls to `next` counts towards it
+    ///
+    /// ```
+    /// # use passchecker::{Checker, Outcome, Policy};
+    /// let checker = Checker::new(Policy::default()).unwrap();
+    /// // Stops at the first failure, the tests after it never run
+    /// let failure = checker
+    ///     .stream("abc")
+    ///     .find(|result| result.outcome == Outcome::Failure)
+    ///     .unwrap();
+    /// assert_eq!(failure.name, "At least 8 characters");
+    /// assert_eq!(checker.stream("abc").count(), checker.tests().len());
+    /// ```
+    pub fn stream<'p>(&self, password: &'p str) -> Results<'_, 'p> {
+        let tests = self.tests();
+        let deadline = self
+            .policy
+            .max_time_ms
+            .map(|budget| Instant::now() + Duration::from_millis(budget));
+        let mut order: Vec<usize> = (0..tests.len()).collect();
+        order.sort_by_key(|&index| tests[index].ignore.cost());
+        Results {
+            checker: self,
+            password,
+            classes: ClassCounts::of(password),
+            tests,
+            order: order.into_iter(),
+            deadline,
+            banned: false,
+        }
+    }
+
+    /// Runs [`Checker::check`] against every password in `passwords` on up to `jobs` threads.
+    /// `on_result` is called on the current thread with the index of each password and its results,
+    /// in the order of `passwords` regardless of which thread finished first
+    ///
+    /// ```
+    /// # use passchecker::{Checker, Policy};
+    /// let checker = Checker::new(Policy::default()).unwrap();
+    /// let passwords = ["password", "hunter2", "correct horse battery staple", "Tr0ub4dor&3"];
+    /// let mut order = Vec::new();
+    /// checker.check_batch(&passwords, 3, |index, results| {
+    ///     let expected = checker.check(passwords[index]);
+    ///     let outcomes = |results: &[_]| results.iter().map(|x: &passchecker::TestResult| x.outcome).collect::<Vec<_>>();
+    ///     assert_eq!(outcomes(&results), outcomes(&expected));
+    ///     order.push(index);
+    /// });
+    /// assert_eq!(order, [0, 1, 2, 3]);
+    /// ```
+    pub fn check_batch<'s>(
+        &'s self,
+        passwords: &[&str],
+        jobs: usize,
+        mut on_result: impl FnMut(usize, Vec<TestResult<'s>>),
+    ) {
+        let next = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+        thread::scope(|scope| {
+            for _ in 0..jobs.clamp(1, passwords.len().max(1)) {
+                let sender = sender.clone();
+                let next = &next;
+                scope.spawn(move || loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(password) = passwords.get(index) else {
+                        break;
+                    };
+                    if sender.send((index, self.check(password))).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(sender);
+            // Hold on to the results that came in before the ones of earlier passwords
+            let mut pending = BTreeMap::new();
+            let mut expected = 0;
+            for (index, results) in receiver {
+                pending.insert(index, results);
+                while let Some(results) = pending.remove(&expected) {
+                    on_result(expected, results);
+                    expected += 1;
+                }
+            }
+        });
+    }
+}
+
+/// The single change that would most improve `password`, based on which of `results` failed: the
+/// remediation of the first failed test by [`SUGGESTION_ORDER`]
+pub fn suggestion(checker: &Checker, password: &str, results: &[TestResult]) -> Option<String> {
+    remediations(checker, password, results).next()
+}
+
+/// The remediations of the tests that failed among `results`, advisory failures included, in
+/// [`SUGGESTION_ORDER`]. Only worked out as the iterator is advanced, some go over the wordlists
+fn remediations<'r>(
+    checker: &'r Checker,
+    password: &'r str,
+    results: &'r [TestResult],
+) -> impl Iterator<Item = String> + 'r {
+    let tests = checker.tests();
+    SUGGESTION_ORDER
+        .into_iter()
+        .filter(|test| {
+            results.iter().any(|result| {
+                &result.ignore == test
+                    && matches!(result.outcome, Outcome::Failure | Outcome::Advisory)
+            })
+        })
+        .filter_map(move |ignore| {
+            let test = tests.iter().find(|test| test.ignore == ignore)?;
+            (test.remediation)(checker, password)
+        })
+}
+
+impl Ignore {
+    /// The remediation a test is created with by [`Test::new`], the concrete step that fixes a
+    /// failure of it
+    pub fn remediation(&self) -> RemediationFn {
+        match self {
+            Ignore::Banned => |checker, password| {
+                let password = password.to_lowercase();
+                let term = checker
+                    .policy
+                    .banned
+                    .iter()
+                    .find(|term| !term.is_empty() && password.contains(&term.to_lowercase()));
+                Some(match term {
+                    Some(term) => format!("Remove the banned term {}", checker.shown(term)),
+                    None => "Avoid the terms banned by the policy".to_string(),
+                })
+            },
+            Ignore::History => |_, _| {
+                Some("Pick a new password instead of reusing or adjusting an old one".to_string())
+            },
+            Ignore::Pwned | Ignore::BreachCount => {
+                |_, _| Some("Pick a password that hasn't appeared in a breach".to_string())
+            }
+            Ignore::Index => |_, _| Some("Avoid passwords from the indexed wordlist".to_string()),
+            Ignore::DefaultPassword => |checker, password| {
+                Some(match default_password(checker, password) {
+                    Some((default, _)) => format!(
+                        "Replace the default password {} with one of your own",
+                        checker.shown(default)
+                    ),
+                    None => "Replace the default password with one of your own".to_string(),
+                })
+            },
+            Ignore::Cracklib => |_, _| Some("Avoid dictionary words".to_string()),
+            Ignore::LeetCollisions => |_, _| {
+                Some(
+                    "Avoid common passwords, even with letters swapped for look-alikes".to_string(),
+                )
+            },
+            Ignore::TransformedCollisions => {
+                |_, _| Some("Avoid common passwords, even reversed or rotated".to_string())
+            }
+            Ignore::Confusables => |_, _| {
+                Some(
+                    "Type the password with a Latin keyboard layout, avoiding look-alikes from other scripts"
+                        .to_string(),
+                )
+            },
+            Ignore::WordlistCollisions | Ignore::NormalizedCollisions => |checker, password| {
+                let policy = &checker.policy;
+                let entries: Vec<&str> = checker
+                    .active_wordlists()
+                    .iter()
+                    .flat_map(|wordlist| wordlist.filtered_entries(policy))
+                    .collect();
+                Some(
+                    match best_match(password, &entries, policy.tie_break, policy.algorithm) {
+                        Some((entry, _)) => {
+                            format!("Avoid the common password {}", checker.shown(&entry))
+                        }
+                        None => "Avoid common passwords".to_string(),
+                    },
+                )
+            },
+            Ignore::Context => {
+                |_, _| Some("Avoid terms related to where the password is used".to_string())
+            }
+            Ignore::Username => |_, _| {
+                Some(
+                    "Avoid your username and email address, even with letters swapped for look-alikes"
+                        .to_string(),
+                )
+            },
+            Ignore::PersonalData => {
+                |_, _| Some("Avoid personal numbers such as phone numbers".to_string())
+            }
+            Ignore::Substrings => {
+                |_, _| Some("Avoid common words, even as part of the password".to_string())
+            }
+            Ignore::ConcatenatedWords | Ignore::WordPairs => {
+                |_, _| Some("Use more and rarer words, or add your own between them".to_string())
+            }
+            Ignore::DictionaryShare => {
+                |_, _| Some("Add more of your own to the common word in the password".to_string())
+            }
+            Ignore::RepeatedPatterns => |_, _| Some("Avoid repeating the same pattern".to_string()),
+            Ignore::Compressibility => |_, _| {
+                Some("Make the whole password count instead of repeating parts of it".to_string())
+            },
+            Ignore::CharacterShare | Ignore::UniqueChars => {
+                |_, _| Some("Use a wider variety of characters".to_string())
+            }
+            Ignore::PatternScore => |_, _| {
+                Some("Avoid predictable parts such as words, dates and sequences".to_string())
+            },
+            Ignore::NgramEntropy => |checker, password| {
+                let missing = f64::from(checker.policy.min_ngram_bits) - ngram::bits(password);
+                Some(format!(
+                    "Add {:.0} bits of unpredictable characters, letters that rarely follow each other or symbols",
+                    missing.max(1.0).ceil()
+                ))
+            },
+            Ignore::MaximumChars => |checker, password| {
+                Some(format!(
+                    "Remove {} characters",
+                    password
+                        .chars()
+                        .count()
+                        .saturating_sub(checker.policy.max_length.unwrap_or(u8::MAX).into())
+                ))
+            },
+            Ignore::MinimumChars => |checker, password| {
+                Some(format!(
+                    "Add {} more characters",
+                    usize::from(checker.policy.min_length).saturating_sub(password.chars().count())
+                ))
+            },
+            Ignore::WordCount => |checker, password| {
+                Some(format!(
+                    "Add {} more words",
+                    usize::from(checker.policy.min_words)
+                        .saturating_sub(password.split_whitespace().count())
+                ))
+            },
+            Ignore::Numbers => |_, _| Some("Add a number".to_string()),
+            Ignore::SpecialChars => |_, _| Some("Add a special character".to_string()),
+            Ignore::NonAscii => |_, _| Some("Only use ASCII characters".to_string()),
+            Ignore::Service => |checker, password| {
+                let service = checker.policy.service.as_deref().and_then(service::find)?;
+                let missing: Vec<String> = service
+                    .rules
+                    .iter()
+                    .filter(|rule| !rule.check(password))
+                    .map(|rule| rule.description())
+                    .collect();
+                Some(format!("Use {} for {}", missing.join(", "), service.title))
+            },
+            Ignore::MatchRegex => |checker, password| {
+                let regex = checker
+                    .match_regexes
+                    .iter()
+                    .find(|regex| !regex.is_match(password))?;
+                Some(format!("Change the password to match {}", regex.as_str()))
+            },
+            Ignore::RejectRegex => |checker, password| {
+                let regex = checker
+                    .reject_regexes
+                    .iter()
+                    .find(|regex| regex.is_match(password))?;
+                Some(format!(
+                    "Change the password so it doesn't match {}",
+                    regex.as_str()
+                ))
+            },
+        }
+    }
+}
+
+fn tests<'a>(policy: &Policy) -> Vec<Test<'a>> {
+    let mut tests = vec![
+        Test::new(
+            format!("At least {} characters", policy.min_length),
+            |checker: &Checker, pass: &str| {
+                let min_length = checker.policy.min_length;
+                let len = pass.chars().count();
+                let outcome = len >= min_length.into();
+                (
+                    outcome.into(),
+                    if outcome {
+                        Cow::Borrowed("")
+                    } else {
+                        Cow::Owned(format!(
+                            "Password too short: {}/{} characters",
+                            len, min_length
+                        ))
+                    },
+                )
+            },
+            Ignore::MinimumChars,
+        ),
+        Test::classes(
+            "numbers".to_string(),
+            |checker: &Checker, classes: &ClassCounts| {
+                if checker.policy.passphrase {
+                    return (
+                        Outcome::Ignored,
+                        Cow::Borrowed("not required for passphrases"),
+                    );
+                }
+                let outcome = classes.digit > 0;
+                (
+                    outcome.into(),
+                    Cow::Borrowed(if outcome {
+                        ""
+                    } else {
+                        "No numeric chacacters in password"
+                    }),
+                )
+            },
+            Ignore::Numbers,
+        ),
+        Test::classes(
+            "quirky characters".to_string(),
+            |checker: &Checker, classes: &ClassCounts| {
+                if checker.policy.passphrase {
+                    return (
+                        Outcome::Ignored,
+                        Cow::Borrowed("not required for passphrases"),
+                    );
+                }
+                let outcome = classes.symbol > 0;
+                (
+                    outcome.into(),
+                    Cow::Borrowed(if outcome {
+                        ""
+                    } else {
+                        "No special chacacters in password"
+                    }),
+                )
+            },
+            Ignore::SpecialChars,
+        ),
+        Test::new(
+            "collisions in wordlist".to_string(),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let mut info = Cow::Borrowed("");
+                // Default to the internal wordlist if no wordlists were provided
+                let wordlists = checker.active_wordlists();
+                if wordlists.is_empty() {
+                    return (Outcome::Ignored, Cow::Borrowed(NO_WORDLIST));
+                }
+                #[cfg(feature = "embedded-wordlist")]
+                if checker.wordlists.is_empty() {
+                    if WORDLIST.entries().is_empty() {
+                        return (
+                            Outcome::Ignored,
+                            Cow::Borrowed(
+                                "The internal wordlist is empty, this build of passchecker is broken. Provide a wordlist with --wordlist instead.",
+                            ),
+                        );
+                    }
+                    info = Cow::Borrowed(
+                        "No wordlist provided, defaulting to internal wordlist(10k most common passwords).",
+                    );
+                }
+                // At this point we have the wordlists set correctly and ensured that the test
+                // should not be ignored
+                let mut outcome: Option<(String, f64)> = None;
+                // The index of the wordlist the best match is in, the line it is on and its rank,
+                // its position among the entries of the wordlist starting from 1
+                let mut location = (0, 0, 0);
+                let mut entry_count = 0;
+                if policy.exact_collisions() {
+                    // Only an identical entry can collide, so there is nothing to compare
+                    let (exact, count) = checker.exact_location(pass);
+                    if let Some(exact) = exact {
+                        outcome = Some((pass.to_string(), 1.0));
+                        location = exact;
+                    }
+                    entry_count = count;
+                } else {
+                    for (wordlist_index, wordlist) in wordlists.iter().enumerate() {
+                        let numbered_entries = wordlist.filtered_numbered_entries(policy);
+                        let entries: Vec<&str> =
+                            numbered_entries.iter().map(|(_, entry)| *entry).collect();
+                        entry_count += entries.len();
+                        let Some((index, similarity)) =
+                            best_match_index(pass, &entries, policy.tie_break, policy.algorithm)
+                        else {
+                            continue;
+                        };
+                        let better = outcome.as_ref().is_none_or(|(best_entry, best)| {
+                            similarity > *best
+                                || (similarity == *best
+                                    && policy.tie_break.prefers(entries[index], best_entry))
+                        });
+                        if better {
+                            outcome = Some((entries[index].to_string(), similarity));
+                            location = (wordlist_index, numbered_entries[index].0, index + 1);
+                        }
+                        // The wordlists are in priority order, the later ones can't change the
+                        // verdict once one of them caught the password
+                        if policy.wordlist_stop_on_hit
+                            && similarity >= policy.similarity_threshold()
+                        {
+                            break;
+                        }
+                    }
+                }
+                let file = |wordlist_index: usize| match policy.wordlists.get(wordlist_index) {
+                    Some(path) => path.display().to_string(),
+                    None if checker.wordlists.is_empty() => "the internal wordlist".to_string(),
+                    None => format!("wordlist {}", wordlist_index + 1),
+                };
+                let (_, _, rank) = location;
+                let significant =
+                    |similarity: f64| similarity * 100.0 >= policy.match_display_threshold.into();
+                // The wordlists are ordered from the most common entry, so a significant match on
+                // one of the first entries is a collision whatever its similarity
+                let top = outcome.as_ref().is_some_and(|(_, similarity)| {
+                    significant(*similarity)
+                        && policy.fail_top.is_some_and(|top| rank <= top as usize)
+                });
+                let warns = |similarity: f64| {
+                    policy
+                        .warn_similarity
+                        .is_some_and(|warn| similarity * 100.0 >= warn.into())
+                };
+                if let Some((checkpass, similarity)) = &outcome {
+                    info = if significant(*similarity) || warns(*similarity) {
+                        let mut info = if policy.wordlist_stop_on_hit {
+                            format!(
+                                "Best match in wordlist {} is {}",
+                                file(location.0),
+                                checker.shown(checkpass)
+                            )
+                        } else {
+                            format!("Best match in wordlist is {}", checker.shown(checkpass))
+                        };
+                        if policy.show_match_rank || top {
+                            info += &format!(", the #{rank} most common entry,");
+                        }
+                        info += &format!(" with similarity {}", percentage(*similarity));
+                        if policy.show_match_location {
+                            let (wordlist_index, line, _) = location;
+                            info += &format!(" (line {line} of {})", file(wordlist_index));
+                        }
+                        Cow::Owned(info)
+                    } else {
+                        Cow::Borrowed("No significant match found in wordlist")
+                    }
+                } else if policy.exact_collisions() {
+                    info = Cow::Borrowed("No identical entry found in wordlist");
+                }
+                if policy.skip_comments {
+                    info.to_mut().push_str(&format!(
+                        " ({entry_count} entries after skipping comments and blank lines)"
+                    ));
+                }
+                if policy.report_threshold {
+                    // Report how close the password is to colliding instead of a verdict
+                    let info = match outcome {
+                        Some((checkpass, similarity)) => format!(
+                            "Collides with any similarity threshold up to {}, the best match in wordlist being {} (current threshold is {}%)",
+                            percentage(similarity),
+                            checker.shown(&checkpass),
+                            policy.similarity
+                        ),
+                        None => "Wordlist is empty, collides with no similarity threshold".to_string(),
+                    };
+                    return (Outcome::Ignored, Cow::Owned(info));
+                }
+                let outcome = match outcome {
+                    Some(_) if top => Outcome::Failure,
+                    Some((_, similarity)) if similarity >= policy.similarity_threshold() => {
+                        Outcome::Failure
+                    }
+                    Some((_, similarity)) if warns(similarity) => Outcome::Warning,
+                    Some(_) => Outcome::Success,
+                    None if entry_count > 0 => Outcome::Success,
+                    None => return (Outcome::Ignored, Cow::Borrowed(EMPTY_WORDLIST)),
+                };
+                (outcome, info)
+            },
+            Ignore::WordlistCollisions,
+        ),
+        Test::new(
+            "normalized collisions in wordlist".to_string(),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let enabled: Vec<Normalization> = Normalization::ALL
+                    .into_iter()
+                    .filter(|normalization| policy.normalizations.contains(normalization))
+                    .collect();
+                if enabled.is_empty() {
+                    return (Outcome::Ignored, Cow::Borrowed("no normalizations enabled"));
+                }
+                let wordlists = checker.active_wordlists();
+                if wordlists.is_empty() {
+                    return (Outcome::Ignored, Cow::Borrowed(NO_WORDLIST));
+                }
+                let entries: Vec<&str> = wordlists
+                    .iter()
+                    .flat_map(|wordlist| wordlist.filtered_entries(policy))
+                    .collect();
+                // Try the combinations with the fewest transformations first, so the reported
+                // ones are all needed to reveal the match
+                let mut combinations: Vec<Vec<Normalization>> = (1..1u32 << enabled.len())
+                    .map(|mask| {
+                        enabled
+                            .iter()
+                            .enumerate()
+                            .filter(|(index, _)| mask & (1 << index) != 0)
+                            .map(|(_, &normalization)| normalization)
+                            .collect()
+                    })
+                    .collect();
+                combinations.sort_by_key(|combination| combination.len());
+                for combination in combinations {
+                    // Reversing both sides would compare them backwards again, so the entries
+                    // only get the normalizations that map characters
+                    let entry_combination: Vec<Normalization> = combination
+                        .iter()
+                        .copied()
+                        .filter(|&normalization| normalization != Normalization::Reverse)
+                        .collect();
+                    let mut normalized_entries = HashMap::new();
+                    for &entry in &entries {
+                        normalized_entries
+                            .entry(normalize(entry, &entry_combination))
+                            .or_insert(entry);
+                    }
+                    let normalized = normalize(pass, &combination);
+                    if let Some(entry) = normalized_entries.get(normalized.as_str()) {
+                        let descriptions: Vec<_> = combination
+                            .iter()
+                            .map(|normalization| normalization.description())
+                            .collect();
+                        let descriptions = match descriptions.split_last() {
+                            Some((last, [])) => last.to_string(),
+                            Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+                            None => unreachable!("combinations are never empty"),
+                        };
+                        return (
+                            Outcome::Failure,
+                            Cow::Owned(format!(
+                                "Matches the wordlist entry {} once {descriptions}",
+                                checker.shown(entry)
+                            )),
+                        );
+                    }
+                }
+                (Outcome::Success, Cow::Borrowed(""))
+            },
+            Ignore::NormalizedCollisions,
+        ),
+        Test::new(
+            "repeated patterns".to_string(),
+            |checker: &Checker, pass: &str| match repeating_unit(pass) {
+                Some((unit, repetitions)) => (
+                    Outcome::Failure,
+                    Cow::Owned(format!(
+                        "Password is {} repeated {repetitions} times",
+                        checker.shown(unit)
+                    )),
+                ),
+                None => (Outcome::Success, Cow::Borrowed("")),
+            },
+            Ignore::RepeatedPatterns,
+        ),
+        Test::new(
+            format!("at most {}% one character", policy.max_character_share),
+            |checker: &Checker, pass: &str| {
+                let mut counts: HashMap<char, usize> = HashMap::new();
+                for ch in pass.chars() {
+                    *counts.entry(ch).or_default() += 1;
+                }
+                // The first of the most frequent characters, so the report doesn't depend on the
+                // order of the map
+                let Some((ch, count)) = pass
+                    .chars()
+                    .map(|ch| (ch, counts[&ch]))
+                    .reduce(|best, next| if next.1 > best.1 { next } else { best })
+                else {
+                    return (Outcome::Success, Cow::Borrowed(""));
+                };
+                let share = count as f64 / pass.chars().count() as f64;
+                if share * 100.0 <= checker.policy.max_character_share.into() {
+                    return (Outcome::Success, Cow::Borrowed(""));
+                }
+                let info = format!(
+                    "The character {} makes up {} of the password",
+                    checker.shown(&format!("{ch:?}")),
+                    percentage(share)
+                );
+                (Outcome::Failure, Cow::Owned(info))
+            },
+            Ignore::CharacterShare,
+        ),
+        Test::new(
+            format!("compresses to at least {}%", policy.min_compression_ratio),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let length = pass.chars().count();
+                if length < policy.compressibility_min_length.into() {
+                    return (Outcome::Success, Cow::Borrowed(""));
+                }
+                let ratio = compressed_length(pass) as f64 / length as f64;
+                if ratio * 100.0 >= policy.min_compression_ratio.into() {
+                    return (Outcome::Success, Cow::Borrowed(""));
+                }
+                let info = format!(
+                    "Long but low-entropy, the {length} characters of the password compress to {} of their length",
+                    percentage(ratio)
+                );
+                (Outcome::Failure, Cow::Owned(info))
+            },
+            Ignore::Compressibility,
+        ),
+        Test::new(
+            format!("at most {}% dictionary word", policy.max_dictionary_share),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let wordlists = checker.active_wordlists();
+                if wordlists.is_empty() {
+                    return (Outcome::Ignored, Cow::Borrowed(NO_WORDLIST));
+                }
+                let pass = pass.to_lowercase();
+                // The longest entry contained in the password explains the largest part of it
+                let mut longest: Option<(String, usize)> = None;
+                for wordlist in wordlists {
+                    for entry in wordlist.filtered_entries(policy) {
+                        let entry = entry.to_lowercase();
+                        let length = entry.chars().count();
+                        if length == 0 || longest.as_ref().is_some_and(|(_, best)| length <= *best)
+                        {
+                            continue;
+                        }
+                        if pass.contains(&entry) {
+                            longest = Some((entry, length));
+                        }
+                    }
+                }
+                let Some((entry, length)) = longest else {
+                    return (Outcome::Success, Cow::Borrowed(""));
+                };
+                let share = length as f64 / pass.chars().count() as f64;
+                let info = format!(
+                    "The wordlist entry {} makes up {} of the password",
+                    checker.shown(&entry),
+                    percentage(share)
+                );
+                let outcome = share * 100.0 <= policy.max_dictionary_share.into();
+                (outcome.into(), Cow::Owned(info))
+            },
+            Ignore::DictionaryShare,
+        ),
+        Test::new(
+            format!("at least 10^{} guesses", policy.min_log10_guesses),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let chunks = checker.decompose(pass);
+                let log10_guesses: f64 = chunks.iter().map(|chunk| chunk.log10_guesses).sum();
+                let decomposition: Vec<String> = chunks
+                    .iter()
+                    .map(|chunk| format!("{} ({})", checker.shown(&chunk.text), chunk.kind))
+                    .collect();
+                let info = format!(
+                    "Estimated 10^{log10_guesses:.1} guesses: {}",
+                    decomposition.join(" + ")
+                );
+                let outcome = log10_guesses >= policy.min_log10_guesses.into();
+                (outcome.into(), Cow::Owned(info))
+            },
+            Ignore::PatternScore,
+        ),
+        Test::new(
+            "personal data".to_string(),
+            |_: &Checker, pass: &str| match personal::detect(pass) {
+                // Only the kind of number is reported, never the number itself
+                Some(pattern) => (
+                    Outcome::Failure,
+                    Cow::Owned(format!("Password looks like a {pattern}")),
+                ),
+                None => (Outcome::Success, Cow::Borrowed("")),
+            },
+            Ignore::PersonalData,
+        ),
+    ];
+    if policy.default_passwords {
+        tests.push(Test::new(
+            "default passwords".to_string(),
+            |checker: &Checker, pass: &str| match default_password(checker, pass) {
+                Some((default, true)) => (
+                    Outcome::Failure,
+                    Cow::Owned(format!(
+                        "Password is the default password {}",
+                        checker.shown(default)
+                    )),
+                ),
+                Some((default, false)) => (
+                    Outcome::Failure,
+                    Cow::Owned(format!(
+                        "Password is close to the default password {}",
+                        checker.shown(default)
+                    )),
+                ),
+                None => (Outcome::Success, Cow::Borrowed("")),
+            },
+            Ignore::DefaultPassword,
+        ));
+    }
+    // First, so a banned password doesn't go through the wordlist scans
+    if !policy.banned.is_empty() {
+        tests.insert(
+            0,
+            Test::new(
+                "banned terms".to_string(),
+                |checker: &Checker, pass: &str| {
+                    let pass = pass.to_lowercase();
+                    for term in &checker.policy.banned {
+                        let lowercase_term = term.to_lowercase();
+                        if lowercase_term.is_empty() {
+                            continue;
+                        }
+                        let info = if pass == lowercase_term {
+                            format!("Password is the banned term {}", checker.shown(term))
+                        } else if pass.contains(&lowercase_term) {
+                            format!("Password contains the banned term {}", checker.shown(term))
+                        } else {
+                            continue;
+                        };
+                        return (Outcome::Failure, Cow::Owned(info));
+                    }
+                    (Outcome::Success, Cow::Borrowed(""))
+                },
+                Ignore::Banned,
+            ),
+        );
+    }
+    if let Some(max_length) = policy.max_length {
+        tests.push(Test::new(
+            format!("At most {} characters", max_length),
+            |checker: &Checker, pass: &str| {
+                let max_length = checker.policy.max_length.unwrap_or(u8::MAX);
+                let len = pass.chars().count();
+                if len <= max_length.into() {
+                    return (Outcome::Success, Cow::Borrowed(""));
+                }
+                (
+                    Outcome::Failure,
+                    Cow::Owned(format!("Password too long: {len}/{max_length} characters")),
+                )
+            },
+            Ignore::MaximumChars,
+        ));
+    }
+    if let Some(min_unique) = policy.min_unique {
+        tests.push(Test::new(
+            format!("at least {} distinct characters", min_unique),
+            |checker: &Checker, pass: &str| {
+                // Characters are Unicode scalar values, so "é" counts once even though it takes
+                // two bytes
+                let unique = pass.chars().collect::<HashSet<char>>().len();
+                let min_unique = checker.policy.min_unique.unwrap_or_default();
+                let outcome = unique >= min_unique.into();
+                let info = format!("{unique} distinct characters");
+                (outcome.into(), Cow::Owned(info))
+            },
+            Ignore::UniqueChars,
+        ));
+    }
+    if policy.passphrase {
+        tests.push(Test::new(
+            format!("At least {} words", policy.min_words),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let words = pass.split_whitespace().count();
+                let outcome = words >= policy.min_words.into();
+                let info = format!(
+                    "{words} words, estimated entropy of {:.1} bits",
+                    words as f64 * DICEWARE_WORD_ENTROPY
+                );
+                (outcome.into(), Cow::Owned(info))
+            },
+            Ignore::WordCount,
+        ));
+    }
+    if !policy.context.is_empty() {
+        tests.push(Test::new(
+            "contextual terms".to_string(),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let pass = pass.to_lowercase();
+                for term in &policy.context {
+                    let lowercase_term = term.to_lowercase();
+                    if lowercase_term.is_empty() {
+                        continue;
+                    }
+                    if pass.contains(&lowercase_term) {
+                        return (
+                            Outcome::Failure,
+                            Cow::Owned(format!(
+                                "Password contains the context term {}",
+                                checker.shown(term)
+                            )),
+                        );
+                    }
+                    let similarity = policy.algorithm.similarity(&lowercase_term, &pass);
+                    if similarity >= policy.similarity_threshold() {
+                        return (
+                            Outcome::Failure,
+                            Cow::Owned(format!(
+                                "Password matches the context term {} with similarity {}",
+                                checker.shown(term),
+                                percentage(similarity)
+                            )),
+                        );
+                    }
+                }
+                (Outcome::Success, Cow::Borrowed(""))
+            },
+            Ignore::Context,
+        ));
+    }
+    if policy.username.is_some() || policy.email.is_some() {
+        tests.push(Test::new(
+            "username and email address".to_string(),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let pass = normalize(pass, &LEET_NORMALIZATIONS);
+                let names = [
+                    policy.username.as_deref().map(|name| ("username", name)),
+                    policy
+                        .email
+                        .as_deref()
+                        .map(|email| ("email address", email_local_part(email))),
+                ];
+                for (kind, name) in names.into_iter().flatten() {
+                    let normalized_name = normalize(name, &LEET_NORMALIZATIONS);
+                    if normalized_name.is_empty() {
+                        continue;
+                    }
+                    if pass.contains(&normalized_name) {
+                        return (
+                            Outcome::Failure,
+                            Cow::Owned(format!(
+                                "Password contains the {kind} {}",
+                                checker.shown(name)
+                            )),
+                        );
+                    }
+                    let similarity = policy.algorithm.similarity(&normalized_name, &pass);
+                    if similarity >= policy.similarity_threshold() {
+                        return (
+                            Outcome::Failure,
+                            Cow::Owned(format!(
+                                "Password matches the {kind} {} with similarity {}",
+                                checker.shown(name),
+                                percentage(similarity)
+                            )),
+                        );
+                    }
+                }
+                (Outcome::Success, Cow::Borrowed(""))
+            },
+            Ignore::Username,
+        ));
+    }
+    if policy.substrings {
+        tests.push(Test::new(
+            format!(
+                "wordlist entries of at least {} characters",
+                policy.substring_min_length
+            ),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let min_length = usize::from(policy.substring_min_length.max(1));
+                // Windows are looked up exactly rather than compared by similarity, which would
+                // cost O(n²·m) similarity comparisons for a password of length n and a wordlist of
+                // m entries. This way the cost is one pass over the wordlist to build the set and
+                // at most n·(longest entry) lookups
+                let wordlists = checker.active_wordlists();
+                if wordlists.is_empty() {
+                    return (Outcome::Ignored, Cow::Borrowed(NO_WORDLIST));
+                }
+                let mut entries = HashSet::new();
+                let mut longest_entry = 0;
+                for wordlist in wordlists {
+                    for entry in wordlist.filtered_entries(policy) {
+                        let entry = entry.to_lowercase();
+                        let length = entry.chars().count();
+                        if length >= min_length {
+                            longest_entry = longest_entry.max(length);
+                            entries.insert(entry);
+                        }
+                    }
+                }
+                let pass = pass.to_lowercase();
+                // The byte offsets every window can start or end at
+                let boundaries: Vec<usize> = pass
+                    .char_indices()
+                    .map(|(index, _)| index)
+                    .chain([pass.len()])
+                    .collect();
+                let chars = boundaries.len() - 1;
+                // The longest embedded entry along with the character it starts at and its length
+                // in characters
+                let mut strongest: Option<(&str, usize, usize)> = None;
+                for start in 0..chars {
+                    let longest_end = chars.min(start + longest_entry);
+                    // Try the longest window first, no shorter one from this start can beat it
+                    for end in (start + min_length..=longest_end).rev() {
+                        let window = &pass[boundaries[start]..boundaries[end]];
+                        if !entries.contains(window) {
+                            continue;
+                        }
+                        if strongest.is_none_or(|(_, _, best)| end - start > best) {
+                            strongest = Some((window, start, end - start));
+                        }
+                        break;
+                    }
+                }
+                match strongest {
+                    Some((entry, start, _)) => (
+                        Outcome::Failure,
+                        Cow::Owned(format!(
+                            "Contains the wordlist entry {} at character {}",
+                            checker.shown(entry),
+                            start + 1
+                        )),
+                    ),
+                    None => (Outcome::Success, Cow::Borrowed("")),
+                }
+            },
+            Ignore::Substrings,
+        ));
+    }
+    if policy.concatenated_words {
+        tests.push(Test::new(
+            "concatenated wordlist entries".to_string(),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let wordlists = checker.active_wordlists();
+                if wordlists.is_empty() {
+                    return (Outcome::Ignored, Cow::Borrowed(NO_WORDLIST));
+                }
+                if pass.chars().count() > MAX_SEGMENTED_LENGTH {
+                    return (
+                        Outcome::Ignored,
+                        Cow::Owned(format!(
+                            "Passwords over {MAX_SEGMENTED_LENGTH} characters aren't split into words"
+                        )),
+                    );
+                }
+                let mut entries = HashSet::new();
+                let mut longest_entry = 0;
+                for wordlist in wordlists {
+                    for entry in wordlist.filtered_entries(policy) {
+                        let entry = entry.to_lowercase();
+                        let length = entry.chars().count();
+                        if length >= MIN_SEGMENT_LENGTH {
+                            longest_entry = longest_entry.max(length);
+                            entries.insert(entry);
+                        }
+                    }
+                }
+                let pass = pass.to_lowercase();
+                match segment(&pass, &entries, longest_entry) {
+                    // A single entry is a plain collision, which the other tests report
+                    Some(words) if words.len() > 1 => {
+                        let words: Vec<Cow<str>> =
+                            words.iter().map(|word| checker.shown(word)).collect();
+                        (
+                            Outcome::Failure,
+                            Cow::Owned(format!(
+                                "Password is made up of the wordlist entries {}",
+                                words.join(" + ")
+                            )),
+                        )
+                    }
+                    _ => (Outcome::Success, Cow::Borrowed("")),
+                }
+            },
+            Ignore::ConcatenatedWords,
+        ));
+    }
+    if policy.word_pairs {
+        tests.push(Test::new(
+            "pairs of wordlist entries".to_string(),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                if checker.active_wordlists().is_empty() {
+                    return (Outcome::Ignored, Cow::Borrowed(NO_WORDLIST));
+                }
+                let length = pass.chars().count();
+                if length > MAX_WORD_PAIR_LENGTH {
+                    return (
+                        Outcome::Ignored,
+                        Cow::Owned(format!(
+                            "Passwords over {MAX_WORD_PAIR_LENGTH} characters aren't split into two words"
+                        )),
+                    );
+                }
+                // The split whose worse half is the closest match, both halves being at least
+                // a segment long
+                let mut best: Option<((String, f64), (String, f64))> = None;
+                let splits = pass
+                    .char_indices()
+                    .map(|(index, _)| index)
+                    .skip(MIN_SEGMENT_LENGTH)
+                    .take((length + 1).saturating_sub(2 * MIN_SEGMENT_LENGTH));
+                for split in splits {
+                    let (first, second) = pass.split_at(split);
+                    let (Some(first), Some(second)) = (
+                        checker.best_wordlist_match(first),
+                        checker.best_wordlist_match(second),
+                    ) else {
+                        continue;
+                    };
+                    let worse = first.1.min(second.1);
+                    if best
+                        .as_ref()
+                        .is_none_or(|(best_first, best_second)| worse > best_first.1.min(best_second.1))
+                    {
+                        best = Some((first, second));
+                    }
+                }
+                match best {
+                    Some(((first, first_similarity), (second, second_similarity)))
+                        if first_similarity.min(second_similarity)
+                            >= policy.similarity_threshold() =>
+                    {
+                        (
+                            Outcome::Failure,
+                            Cow::Owned(format!(
+                                "Password is the wordlist entries {} + {} with similarities {} and {}",
+                                checker.shown(&first),
+                                checker.shown(&second),
+                                percentage(first_similarity),
+                                percentage(second_similarity)
+                            )),
+                        )
+                    }
+                    _ => (Outcome::Success, Cow::Borrowed("")),
+                }
+            },
+            Ignore::WordPairs,
+        ));
+    }
+    if !policy.transformations.is_empty() {
+        tests.push(Test::new(
+            "transformed collisions in wordlist".to_string(),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                if checker.active_wordlists().is_empty() {
+                    return (Outcome::Ignored, Cow::Borrowed(NO_WORDLIST));
+                }
+                // The transformations undo themselves, so comparing the transformed password
+                // against the plain wordlist is the same as comparing the password against the
+                // transformed wordlist, without transforming every entry
+                let mut best: Option<(Transformation, String, f64)> = None;
+                for transformation in policy.transformations.iter().copied() {
+                    let transformed = transformation.apply(pass);
+                    // A palindrome or a password without letters is left as is, the plain
+                    // collision test already covers it
+                    if transformed == pass {
+                        continue;
+                    }
+                    if let Some((entry, similarity)) = checker.best_wordlist_match(&transformed) {
+                        if best.as_ref().is_none_or(|(_, _, best)| similarity > *best) {
+                            best = Some((transformation, entry, similarity));
+                        }
+                    }
+                }
+                let Some((transformation, entry, similarity)) = best else {
+                    return (Outcome::Success, Cow::Borrowed(""));
+                };
+                if similarity * 100.0 < policy.match_display_threshold.into() {
+                    return (Outcome::Success, Cow::Borrowed(""));
+                }
+                let info = format!(
+                    "Best match in wordlist for the {} password is {} with similarity {}",
+                    transformation.description(),
+                    checker.shown(&entry),
+                    percentage(similarity)
+                );
+                let outcome = similarity < policy.similarity_threshold();
+                (outcome.into(), Cow::Owned(info))
+            },
+            Ignore::TransformedCollisions,
+        ));
+    }
+    if policy.leet_collisions {
+        tests.push(Test::new(
+            "leet variants of wordlist entries".to_string(),
+            |checker: &Checker, pass: &str| {
+                if checker.active_wordlists().is_empty() {
+                    return (Outcome::Ignored, Cow::Borrowed(NO_WORDLIST));
+                }
+                let lowercase = pass.to_lowercase();
+                let normalized = Normalization::Leet.apply(&lowercase);
+                // Without any look-alike to undo this is a plain collision, which the collision
+                // test reports
+                if normalized == lowercase {
+                    return (Outcome::Success, Cow::Borrowed(""));
+                }
+                match checker.leet_entry(&normalized) {
+                    Some(entry) => (
+                        Outcome::Failure,
+                        Cow::Owned(format!(
+                            "Password is the wordlist entry {} with look-alike substitutions",
+                            checker.shown(entry)
+                        )),
+                    ),
+                    None => (Outcome::Success, Cow::Borrowed("")),
+                }
+            },
+            Ignore::LeetCollisions,
+        ));
+    }
+    if policy.confusables {
+        tests.push(Test::new(
+            "look-alikes from other scripts".to_string(),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let count = confusables::count(pass);
+                if count == 0 {
+                    return (Outcome::Success, Cow::Borrowed(""));
+                }
+                let skeleton = confusables::skeleton(pass);
+                if let Some((entry, similarity)) = checker.best_wordlist_match(&skeleton) {
+                    if similarity >= policy.similarity_threshold() {
+                        return (
+                            Outcome::Failure,
+                            Cow::Owned(format!(
+                                "Password written in ASCII matches the wordlist entry {} with similarity {}",
+                                checker.shown(&entry),
+                                percentage(similarity)
+                            )),
+                        );
+                    }
+                }
+                // Only counted, the characters themselves are part of the password
+                let info = format!(
+                    "Password contains {count} {} like ASCII",
+                    if count == 1 { "character that looks" } else { "characters that look" }
+                );
+                (Outcome::Warning, Cow::Owned(info))
+            },
+            Ignore::Confusables,
+        ));
+    }
+    if policy.cracklib {
+        tests.push(Test::new(
+            "cracklib dictionary".to_string(),
+            |checker: &Checker, pass: &str| {
+                let policy = &checker.policy;
+                let Some((ref path, ref dictionary)) = checker.cracklib else {
+                    return (
+                        Outcome::Ignored,
+                        Cow::Borrowed("no cracklib dictionary given"),
+                    );
+                };
+                // The dictionaries are lowercased when they are packed
+                let pass = pass.to_lowercase();
+                let entries = dictionary.filtered_entries(policy);
+                let Some((index, similarity)) =
+                    best_match_index(&pass, &entries, policy.tie_break, policy.algorithm)
+                else {
+                    return (
+                        Outcome::Ignored,
+                        Cow::Owned(format!(
+                            "The cracklib dictionary {} is empty",
+                            path.display()
+                        )),
+                    );
+                };
+                let outcome = similarity < policy.similarity_threshold();
+                if outcome && similarity * 100.0 < policy.match_display_threshold.into() {
+                    return (
+                        Outcome::Success,
+                        Cow::Borrowed("No significant match found in the cracklib dictionary"),
+                    );
+                }
+                (
+                    outcome.into(),
+                    Cow::Owned(format!(
+                        "Best match in the cracklib dictionary {} is {} with similarity {}",
+                        path.display(),
+                        checker.shown(entries[index]),
+                        percentage(similarity)
+                    )),
+                )
+            },
+            Ignore::Cracklib,
+        ));
+    }
+    if let Some(ref name) = policy.service {
+        tests.push(Test::new(
+            format!("accepted by {}", name),
+            |checker: &Checker, pass: &str| {
+                let name = checker.policy.service.as_deref().unwrap_or_default();
+                let Some(service) = service::find(name) else {
+                    let names: Vec<&str> = service::SERVICES
+                        .iter()
+                        .map(|service| service.name)
+                        .collect();
+                    return (
+                        Outcome::Ignored,
+                        Cow::Owned(format!(
+                            "Unknown service {name}, known ones are {}",
+                            names.join(", ")
+                        )),
+                    );
+                };
+                let (met, missing): (Vec<_>, Vec<_>) =
+                    service.rules.iter().partition(|rule| rule.check(pass));
+                let describe = |rules: Vec<&service::Rule>| {
+                    rules
+                        .iter()
+                        .map(|rule| rule.description())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                if missing.is_empty() {
+                    return (
+                        Outcome::Success,
+                        Cow::Owned(format!(
+                            "Accepted by {}, met {}",
+                            service.title,
+                            describe(met)
+                        )),
+                    );
+                }
+                let mut info = format!(
+                    "Rejected by {}, missing {}",
+                    service.title,
+                    describe(missing)
+                );
+                if !met.is_empty() {
+                    info += &format!("; met {}", describe(met));
+                }
+                (Outcome::Failure, Cow::Owned(info))
+            },
+            Ignore::Service,
+        ));
+    }
+    if policy.ngram_entropy {
+        tests.push(Test::new(
+            format!("at least {} bits of n-gram entropy", policy.min_ngram_bits),
+            |checker: &Checker, pass: &str| {
+                let bits = ngram::bits(pass);
+                let info = format!("Estimated {bits:.1} bits from English letter pair frequencies");
+                let outcome = bits >= checker.policy.min_ngram_bits.into();
+                (outcome.into(), Cow::Owned(info))
+            },
+            Ignore::NgramEntropy,
+        ));
+    }
+    if !policy.match_regex.is_empty() {
+        tests.push(Test::new(
+            "required formats".to_string(),
+            |checker: &Checker, pass: &str| match checker
+                .match_regexes
+                .iter()
+                .find(|regex| !regex.is_match(pass))
+            {
+                Some(regex) => (
+                    Outcome::Failure,
+                    Cow::Owned(format!(
+                        "Password doesn't match the required format {}",
+                        regex.as_str()
+                    )),
+                ),
+                None => {
+                    let patterns: Vec<&str> =
+                        checker.match_regexes.iter().map(Regex::as_str).collect();
+                    (
+                        Outcome::Success,
+                        Cow::Owned(format!("Password matches {}", patterns.join(", "))),
+                    )
+                }
+            },
+            Ignore::MatchRegex,
+        ));
+    }
+    if !policy.reject_regex.is_empty() {
+        tests.push(Test::new(
+            "rejected formats".to_string(),
+            |checker: &Checker, pass: &str| {
+                let rejected = checker
+                    .reject_regexes
+                    .iter()
+                    .find_map(|regex| Some((regex, regex.find(pass)?)));
+                match rejected {
+                    Some((regex, found)) => (
+                        Outcome::Failure,
+                        Cow::Owned(format!(
+                            "Password matches the rejected format {} with {}",
+                            regex.as_str(),
+                            checker.shown(found.as_str())
+                        )),
+                    ),
+                    None => (Outcome::Success, Cow::Borrowed("")),
+                }
+            },
+            Ignore::RejectRegex,
+        ));
+    }
+    if policy.ascii_only {
+        tests.push(Test::new(
+            "only ASCII characters".to_string(),
+            |_: &Checker, pass: &str| {
+                let non_ascii: Vec<char> = pass.chars().filter(|ch| !ch.is_ascii()).collect();
+                if non_ascii.is_empty() {
+                    return (Outcome::Success, Cow::Borrowed(""));
+                }
+                let mut examples: Vec<char> = Vec::new();
+                for ch in &non_ascii {
+                    if examples.len() < 5 && !examples.contains(ch) {
+                        examples.push(*ch);
+                    }
+                }
+                let examples: Vec<String> = examples.iter().map(|ch| format!("{ch:?}")).collect();
+                (
+                    Outcome::Failure,
+                    Cow::Owned(format!(
+                        "{} non-ASCII characters in password, such as {}",
+                        non_ascii.len(),
+                        examples.join(", ")
+                    )),
+                )
+            },
+            Ignore::NonAscii,
+        ));
+    }
+    if policy.pwned_dir.is_some() {
+        tests.push(Test::new(
+            "breached passwords".to_string(),
+            |checker: &Checker, pass: &str| {
+                let Some(ref dir) = checker.policy.pwned_dir else {
+                    return (
+                        Outcome::Ignored,
+                        Cow::Borrowed("no Pwned Passwords directory given"),
+                    );
+                };
+                breached(dir, &sha1_hex(pass))
+            },
+            Ignore::Pwned,
+        ));
+    }
+    if policy.breach_counts.is_some() {
+        tests.push(Test::new(
+            "breach counts".to_string(),
+            |checker: &Checker, pass: &str| {
+                let Some((ref path, ref counts)) = checker.breach_counts else {
+                    return (Outcome::Ignored, Cow::Borrowed("no counted wordlist given"));
+                };
+                let threshold = checker.policy.breach_threshold;
+                match counts.get(pass) {
+                    Some(&count) if count > threshold => (
+                        Outcome::Failure,
+                        Cow::Owned(format!(
+                            "Seen {count} times in {}, over the threshold of {threshold}",
+                            path.display()
+                        )),
+                    ),
+                    Some(&count) => (
+                        Outcome::Success,
+                        Cow::Owned(format!(
+                            "Seen {count} times in {}, within the threshold of {threshold}",
+                            path.display()
+                        )),
+                    ),
+                    None => (Outcome::Success, Cow::Borrowed("")),
+                }
+            },
+            Ignore::BreachCount,
+        ));
+    }
+    if policy.index.is_some() {
+        tests.push(Test::new(
+            "indexed wordlist".to_string(),
+            |checker: &Checker, pass: &str| {
+                let Some(ref index) = checker.index else {
+                    return (Outcome::Ignored, Cow::Borrowed("no index given"));
+                };
+                if index.is_stale() {
+                    return (
+                        Outcome::Ignored,
+                        Cow::Owned(format!(
+                            "{} changed since the index was built, rebuild it with build-index",
+                            index.source.display()
+                        )),
+                    );
+                }
+                match index.contains(pass) {
+                    Ok(true) => (
+                        Outcome::Failure,
+                        Cow::Owned(format!(
+                            "Password is one of the {} entries of the index",
+                            index.count
+                        )),
+                    ),
+                    Ok(false) => (Outcome::Success, Cow::Borrowed("")),
+                    Err(err) => (
+                        Outcome::Ignored,
+                        Cow::Owned(format!(
+                            "Failed to read the index {}: {err}",
+                            index.path.display()
+                        )),
+                    ),
+                }
+            },
+            Ignore::Index,
+        ));
+    }
+    if policy.history.is_some() {
+        tests.push(Test::new(
+            "previous passwords".to_string(),
+            |checker: &Checker, pass: &str| {
+                let Some(ref history) = checker.history else {
+                    return (Outcome::Ignored, Cow::Borrowed("no password history given"));
+                };
+                let threshold = checker.policy.similarity_threshold();
+                let mut hash = None;
+                for (index, entry) in history.lines().enumerate() {
+                    // Entries are identified by their line so the old passwords are never printed
+                    let line = index + 1;
+                    if let Some(entry_hash) = entry.strip_prefix("sha1:") {
+                        // Hashed entries can only be matched exactly
+                        let hash = hash.get_or_insert_with(|| sha1_hex(pass));
+                        if entry_hash.trim().eq_ignore_ascii_case(hash) {
+                            return (
+                                Outcome::Failure,
+                                Cow::Owned(format!("Password is the previous password on line {line}")),
+                            );
+                        }
+                        continue;
+                    }
+                    if entry.is_empty() {
+                        continue;
+                    }
+                    if entry == pass {
+                        return (
+                            Outcome::Failure,
+                            Cow::Owned(format!("Password is the previous password on line {line}")),
+                        );
+                    }
+                    let similarity = checker.policy.algorithm.similarity(entry, pass);
+                    if similarity >= threshold {
+                        return (
+                            Outcome::Failure,
+                            Cow::Owned(format!(
+                                "Password resembles the previous password on line {line} with similarity {}",
+                                percentage(similarity)
+                            )),
+                        );
+                    }
+                }
+                (Outcome::Success, Cow::Borrowed(""))
+            },
+            Ignore::History,
+        ));
+    }
+    tests
+}
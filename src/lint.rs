@@ -0,0 +1,81 @@
+//! Statistics about a wordlist, reported by `passchecker lint-wordlist` to help curate wordlists
+//! before checking against them
+use crate::{ExternalWordlist, Policy, Wordlist};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// How many of the first and last entries are reported as the most and least common ones
+pub const SAMPLE_SIZE: usize = 5;
+
+/// The statistics of a wordlist. Entries are the lines the tests compare against, so blank lines
+/// and comments only count as entries unless the policy skips them
+#[derive(Serialize, Debug, Clone)]
+pub struct Lint {
+    pub lines: usize,
+    pub entries: usize,
+    pub unique: usize,
+    /// How many entries are repeats of an earlier one
+    pub duplicates: usize,
+    pub blank: usize,
+    /// Lines starting with [`Policy::comment_prefix`]
+    pub comments: usize,
+    /// Lines that aren't valid UTF-8, they are compared against with the invalid bytes replaced
+    pub invalid_utf8: usize,
+    /// The average length of the entries, in characters
+    pub average_length: f64,
+    /// The first unique entries, wordlists being ordered from the most common entry
+    pub most_common: Vec<String>,
+    /// The last unique entries
+    pub least_common: Vec<String>,
+}
+
+impl Lint {
+    /// The statistics of the wordlist `bytes`, filtered like `policy` filters wordlists
+    ///
+    /// ```
+    /// use passchecker::{lint::Lint, Policy};
+    ///
+    /// let lint = Lint::new(b"password\n123456\n\n# comment\npassword\n\xff\n", &Policy::default());
+    /// assert_eq!((lint.lines, lint.entries, lint.unique), (6, 6, 5));
+    /// assert_eq!((lint.duplicates, lint.blank, lint.comments, lint.invalid_utf8), (1, 1, 1, 1));
+    /// assert_eq!(lint.most_common[..2], ["password", "123456"]);
+    /// ```
+    pub fn new(bytes: &[u8], policy: &Policy) -> Self {
+        let invalid_utf8 = bytes
+            .split(|&byte| byte == b'\n')
+            .filter(|line| std::str::from_utf8(line).is_err())
+            .count();
+        let wordlist = ExternalWordlist(String::from_utf8_lossy(bytes).into_owned());
+        let lines = wordlist.entries();
+        let blank = lines.iter().filter(|line| line.trim().is_empty()).count();
+        let comments = lines
+            .iter()
+            .filter(|line| !line.trim().is_empty() && line.starts_with(&policy.comment_prefix))
+            .count();
+        let entries = wordlist.filtered_entries(policy);
+        let mut seen = HashSet::new();
+        let unique: Vec<&str> = entries
+            .iter()
+            .copied()
+            .filter(|entry| seen.insert(*entry))
+            .collect();
+        let total_length: usize = entries.iter().map(|entry| entry.chars().count()).sum();
+        let sample = |entries: &[&str]| entries.iter().map(|entry| entry.to_string()).collect();
+        Self {
+            lines: lines.len(),
+            entries: entries.len(),
+            unique: unique.len(),
+            duplicates: entries.len() - unique.len(),
+            blank,
+            comments,
+            invalid_utf8,
+            average_length: if entries.is_empty() {
+                0.0
+            } else {
+                total_length as f64 / entries.len() as f64
+            },
+            most_common: sample(&unique[..unique.len().min(SAMPLE_SIZE)]),
+            least_common: sample(&unique[unique.len().saturating_sub(SAMPLE_SIZE)..]),
+        }
+    }
+}
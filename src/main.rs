@@ -1,303 +1,2129 @@
-use clap::Parser;
-use const_format::{str_replace, str_split};
+use clap::{builder::PossibleValuesParser, Parser, Subcommand};
 use owo_colors::{
-    OwoColorize,
-    Stream::{Stderr, Stdout},
-    Style,
+    AnsiColors, DynColors, OwoColorize,
+    Stream::{self, Stderr, Stdout},
+    Style, XtermColors,
 };
-use similar_string::find_best_similarity;
-use std::io::{stdin, stdout, Write};
-use std::path::PathBuf;
-use std::{borrow::Cow, fs::read_to_string};
-
-#[derive(Debug, Clone)]
-enum WordlistType {
-    Internal([&'static str; str_split!(include_str!("../wordlist"), '\n').len()]),
-    External(String),
-}
-static WORDLIST: WordlistType = WordlistType::Internal(str_split!(
-    str_replace!(include_str!("../wordlist"), '\r', ""),
-    '\n'
-));
+use passchecker::config::Profile;
+use passchecker::counts::CountFormat;
+use passchecker::lint::Lint;
+use passchecker::report::{Audit, Csv, Delimiter, Format, Report};
+use passchecker::service::SERVICES;
+use passchecker::{
+    best_match, compression, index, mask, matches_above, parse_sha1, percentage, suggestion,
+    Algorithm, Checker, Config, ExternalWordlist, Ignore, Normalization, Outcome, Policy, Tally,
+    TestResult, TieBreak, Tier, Transformation, Wordlist, BUDGET_EXHAUSTED, NEEDS_PLAINTEXT,
+};
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::fs::{read_to_string, File, OpenOptions};
+use std::io::{stderr, stdin, stdout, IsTerminal, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[derive(Parser)]
 #[command(author = "CordlessCoder", version, about, long_about = None)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
     /// The password to check
     password: Option<String>,
 
-    /// Sets what wordlist to check against, if not specified defaults to the internal wordlist
-    #[arg(short, long, value_name = "FILE")]
-    wordlist: Option<PathBuf>,
+    /// Sets what wordlist to check against, can be given multiple times, if not specified defaults to the internal wordlist. - reads it from STDIN, the password then has to be given as an argument. Wordlists compressed with gzip, zstd or bzip2 are decompressed
+    #[arg(short, long, value_name = "FILE", env = "PASSCHECKER_WORDLIST")]
+    wordlist: Vec<PathBuf>,
+
+    /// Ignores blank lines and comments in the wordlist
+    #[arg(long, env = "PASSCHECKER_WORDLIST_SKIP_COMMENTS")]
+    wordlist_skip_comments: bool,
+
+    /// Removes the repeated entries of each wordlist as it is read, keeping the first of them, and reports how many were removed
+    #[arg(long, env = "PASSCHECKER_DEDUP_WORDLIST")]
+    dedup_wordlist: bool,
+
+    /// Skips wordlist entries shorter than this many characters, which match parts of any password, and reports how many were skipped
+    #[arg(long, value_name = "N", env = "PASSCHECKER_WORDLIST_MIN_LENGTH")]
+    wordlist_min_length: Option<u8>,
+
+    /// What comment lines in the wordlist start with, defaults to #
+    #[arg(long, value_name = "PREFIX", env = "PASSCHECKER_COMMENT_PREFIX")]
+    comment_prefix: Option<String>,
 
     /// Overrides the minimum length of the password
-    #[arg(short, long, value_name = "MINIMUM LENGTH")]
+    #[arg(
+        short,
+        long,
+        value_name = "MINIMUM LENGTH",
+        env = "PASSCHECKER_MIN_LENGTH"
+    )]
     min_length: Option<u8>,
 
+    /// Rejects passwords longer than this, for systems that can't store longer ones
+    #[arg(long, value_name = "MAXIMUM LENGTH", env = "PASSCHECKER_MAX_LENGTH")]
+    max_length: Option<u8>,
+
     /// Which tests to ignore, optional
-    #[arg(short, long, value_enum, value_name = "IGNORE")]
+    #[arg(
+        short,
+        long,
+        value_enum,
+        value_name = "IGNORE",
+        value_delimiter = ',',
+        env = "PASSCHECKER_IGNORE"
+    )]
     ignore: Option<Vec<Ignore>>,
 
+    /// Which tests are only advisory: their failures are reported, but the password is accepted or rejected on the other tests alone
+    #[arg(
+        long,
+        value_enum,
+        value_name = "TEST",
+        value_delimiter = ',',
+        env = "PASSCHECKER_ADVISORY"
+    )]
+    advisory: Option<Vec<Ignore>>,
+
     /// The minimum percentage match required for a match to be considered a collision
-    #[arg(short, long, value_name = "MINIMUM SIMILARITY")]
+    #[arg(short, long, value_name = "MINIMUM SIMILARITY", value_parser = clap::value_parser!(u8).range(0..=100), env = "PASSCHECKER_SIMILARITY")]
     similarity: Option<u8>,
+
+    /// The similarity percentage from which a match that doesn't collide is reported as a warning instead of a success
+    #[arg(long, value_name = "PERCENTAGE", value_parser = clap::value_parser!(u8).range(0..=100), env = "PASSCHECKER_WARN_SIMILARITY")]
+    warn_similarity: Option<u8>,
+
+    /// The similarity percentage below which the best wordlist match isn't reported, defaults to 30
+    #[arg(long, value_name = "PERCENTAGE", value_parser = clap::value_parser!(u8).range(0..=100), env = "PASSCHECKER_MATCH_DISPLAY_THRESHOLD")]
+    match_display_threshold: Option<u8>,
+
+    /// Which transformations the normalized collision test undoes before comparing against the wordlist, defaults to all of them
+    #[arg(
+        long,
+        value_enum,
+        value_name = "NORMALIZATION",
+        value_delimiter = ',',
+        env = "PASSCHECKER_NORMALIZATIONS"
+    )]
+    normalizations: Option<Vec<Normalization>>,
+
+    /// The largest percentage of the password a single character may make up, even spread out, defaults to 40
+    #[arg(long, value_name = "PERCENTAGE", value_parser = clap::value_parser!(u8).range(0..=100), env = "PASSCHECKER_MAX_CHARACTER_SHARE")]
+    max_character_share: Option<u8>,
+
+    /// Requires at least this many distinct characters in the password
+    #[arg(long, value_name = "COUNT", env = "PASSCHECKER_MIN_UNIQUE")]
+    min_unique: Option<u8>,
+
+    /// The length from which the password is checked for compressibility, defaults to 20
+    #[arg(
+        long,
+        value_name = "LENGTH",
+        env = "PASSCHECKER_COMPRESSIBILITY_MIN_LENGTH"
+    )]
+    compressibility_min_length: Option<u8>,
+
+    /// The smallest percentage of its length a long password may compress to, defaults to 50. Catches passwords padded out with repetition
+    #[arg(long, value_name = "PERCENTAGE", value_parser = clap::value_parser!(u8).range(0..=100), env = "PASSCHECKER_MIN_COMPRESSION_RATIO")]
+    min_compression_ratio: Option<u8>,
+
+    /// The largest percentage of the password a single wordlist entry may make up, defaults to 70
+    #[arg(long, value_name = "PERCENTAGE", value_parser = clap::value_parser!(u8).range(0..=100), env = "PASSCHECKER_MAX_DICTIONARY_SHARE")]
+    max_dictionary_share: Option<u8>,
+
+    /// The estimated guesses the password has to take, as a power of 10, defaults to 10
+    #[arg(long, value_name = "EXPONENT", env = "PASSCHECKER_MIN_LOG10_GUESSES")]
+    min_log10_guesses: Option<u8>,
+
+    /// Also estimates the entropy of the password from how often English letters follow each other, so predictable letter sequences score lower than random ones
+    #[arg(long, env = "PASSCHECKER_NGRAM_ENTROPY")]
+    ngram_entropy: bool,
+
+    /// The entropy in bits --ngram-entropy requires, defaults to 40
+    #[arg(long, value_name = "BITS", env = "PASSCHECKER_MIN_NGRAM_BITS")]
+    min_ngram_bits: Option<u8>,
+
+    /// Starts from a preset policy, which the config file and other flags override
+    #[arg(long, value_enum, value_name = "PROFILE", env = "PASSCHECKER_PROFILE")]
+    profile: Option<Profile>,
+
+    /// Instead of passing or failing the collision test, reports the highest similarity threshold at which the password would collide
+    #[arg(long, env = "PASSCHECKER_REPORT_THRESHOLD")]
+    report_threshold: bool,
+
+    /// Shows the file and line the best match in the wordlists was found on
+    #[arg(long, env = "PASSCHECKER_SHOW_MATCH_LOCATION")]
+    show_match_location: bool,
+
+    /// How the similarity of the password and wordlist entries is computed, weighted makes look-alike substitutions such as 0 for o cost less than other changes
+    #[arg(
+        long,
+        value_enum,
+        value_name = "ALGORITHM",
+        env = "PASSCHECKER_ALGORITHM"
+    )]
+    algorithm: Option<Algorithm>,
+
+    /// Which of several wordlist entries equally similar to the password is reported as the best match, defaults to the first one in the wordlists
+    #[arg(
+        long,
+        value_enum,
+        value_name = "TIE BREAK",
+        env = "PASSCHECKER_TIE_BREAK"
+    )]
+    tie_break: Option<TieBreak>,
+
+    /// Shows the rank of the best match in its wordlist, such as "#3 most common", for wordlists ordered from the most common entry
+    #[arg(long, env = "PASSCHECKER_SHOW_MATCH_RANK")]
+    show_match_rank: bool,
+
+    /// Fails the collision test if the best match is one of the COUNT most common entries of its wordlist and at least as similar as --match-display-threshold, whatever --similarity is
+    #[arg(long, value_name = "COUNT", env = "PASSCHECKER_FAIL_TOP")]
+    fail_top: Option<u32>,
+
+    /// Checks the wordlists in the order they were given and stops at the first one the password collides with, naming it
+    #[arg(long, env = "PASSCHECKER_WORDLIST_STOP_ON_HIT")]
+    wordlist_stop_on_hit: bool,
+
+    /// Reads the policy from a TOML file, flags given on the command line take precedence
+    #[arg(short, long, value_name = "FILE", env = "PASSCHECKER_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Reads the policy from a JSON object given inline, with the same keys as the config file, instead of from a file
+    #[arg(
+        long,
+        value_name = "JSON",
+        conflicts_with = "config",
+        env = "PASSCHECKER_POLICY_JSON"
+    )]
+    policy_json: Option<String>,
+
+    /// Prints the policy resulting from the profile, config file and flags as a config file and exits
+    #[arg(long)]
+    print_config: bool,
+
+    /// Suppresses advisories, such as the one about the size of the internal wordlist
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// A file of pre-approved passwords, one per line, if the password is one of them all tests are skipped
+    #[arg(short, long, value_name = "FILE", env = "PASSCHECKER_ALLOWLIST")]
+    allowlist: Option<PathBuf>,
+
+    /// A directory of downloaded Pwned Passwords SHA-1 range files, the password is looked up in them without network access
+    #[arg(long, value_name = "DIR", env = "PASSCHECKER_PWNED_DIR")]
+    pwned_dir: Option<PathBuf>,
+
+    /// A file of previous passwords, one per line, that the password shouldn't match or resemble, lines starting with sha1: are hex SHA-1 hashes
+    #[arg(long, value_name = "FILE", env = "PASSCHECKER_HISTORY")]
+    history: Option<PathBuf>,
+
+    /// An index built with build-index, the password is looked up in it exactly without reading the whole wordlist
+    #[arg(long, value_name = "FILE", env = "PASSCHECKER_INDEX")]
+    index: Option<PathBuf>,
+
+    /// Checks the password as a passphrase, requiring a number of words instead of numbers and special characters
+    #[arg(long, env = "PASSCHECKER_PASSPHRASE")]
+    passphrase: bool,
+
+    /// Rejects passwords containing non-ASCII characters, for systems that only accept ASCII
+    #[arg(long, env = "PASSCHECKER_ASCII_ONLY")]
+    ascii_only: bool,
+
+    /// Overrides the minimum number of words in a passphrase
+    #[arg(long, value_name = "MINIMUM WORDS", env = "PASSCHECKER_MIN_WORDS")]
+    min_words: Option<u8>,
+
+    /// Also looks for wordlist entries embedded anywhere in the password, such as the "password" in "xYpasswordQ"
+    #[arg(long, env = "PASSCHECKER_SUBSTRINGS")]
+    substrings: bool,
+
+    /// Also checks whether the password is made up entirely of wordlist entries, such as "redbluegreen"
+    #[arg(long, env = "PASSCHECKER_CONCATENATED_WORDS")]
+    concatenated_words: bool,
+
+    /// Also checks whether the password is two common words put together, such as "sunshinebaseball", even if the words are slightly off
+    #[arg(long, env = "PASSCHECKER_WORD_PAIRS")]
+    word_pairs: bool,
+
+    /// Also compares these transformations of the password against the wordlist, such as "cnffjbeq" in ROT13
+    #[arg(
+        long,
+        value_enum,
+        value_name = "TRANSFORMATION",
+        value_delimiter = ',',
+        env = "PASSCHECKER_TRANSFORMATIONS"
+    )]
+    transformations: Option<Vec<Transformation>>,
+
+    /// Also looks the password up exactly in the wordlist once look-alikes such as 0 for o are undone, through a map built once even for a batch
+    #[arg(long, env = "PASSCHECKER_LEET_COLLISIONS")]
+    leet_collisions: bool,
+
+    /// Warns about characters from other scripts that look like ASCII ones, such as the Cyrillic а, and fails if the password written in ASCII matches the wordlist
+    #[arg(long, env = "PASSCHECKER_CHECK_CONFUSABLES")]
+    check_confusables: bool,
+
+    /// Also compares the password against the cracklib dictionary installed on the system, found in the standard paths
+    #[arg(long, env = "PASSCHECKER_CRACKLIB")]
+    cracklib: bool,
+
+    /// The cracklib dictionary to compare against, a packed one without its .pwd extension or a plain word file, implies --cracklib
+    #[arg(long, value_name = "DICT", env = "PASSCHECKER_CRACKLIB_DICT")]
+    cracklib_dict: Option<PathBuf>,
+
+    /// A wordlist of passwords along with how often they were seen in breaches, a password seen more than --breach-threshold times fails
+    #[arg(long, value_name = "FILE", env = "PASSCHECKER_BREACH_COUNTS")]
+    breach_counts: Option<PathBuf>,
+
+    /// How the counts of --breach-counts are written
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        env = "PASSCHECKER_COUNT_FORMAT"
+    )]
+    count_format: Option<CountFormat>,
+
+    /// The number of breaches over which --breach-counts fails the password
+    #[arg(long, value_name = "COUNT", env = "PASSCHECKER_BREACH_THRESHOLD")]
+    breach_threshold: Option<u64>,
+
+    /// The length of the shortest wordlist entry --substrings looks for, defaults to 4
+    #[arg(long, value_name = "LENGTH", env = "PASSCHECKER_SUBSTRING_MIN_LENGTH")]
+    substring_min_length: Option<u8>,
+
+    /// Instead of running the tests, shows the best match of the password in each of the two given wordlists side by side
+    #[arg(long)]
+    compare: bool,
+
+    /// Instead of running the tests, lists every wordlist entry more similar to the password than this percentage, from the most similar
+    #[arg(long, value_name = "CUTOFF", value_parser = clap::value_parser!(u8).range(0..=100), conflicts_with_all = ["compare", "format", "check", "pam", "confirm"])]
+    dump_matches: Option<u8>,
+
+    /// Prints a machine readable report to STDOUT instead of the human readable one
+    #[arg(short, long, value_enum, value_name = "FORMAT")]
+    format: Option<Format>,
+
+    /// Also writes a machine readable report, without colors, to a file
+    #[arg(short, long, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Appends a timestamped JSON line with the outcome of every password checked to a file, for an audit trail. The password is masked
+    #[arg(long, value_name = "FILE", conflicts_with = "compare")]
+    report_file: Option<PathBuf>,
+
+    /// Leaves the masked password out of the --report-file lines
+    #[arg(long, requires = "report_file")]
+    report_no_password: bool,
+
+    /// Adds the outcome of each test, without its info, to the --report-file lines
+    #[arg(long, requires = "report_file")]
+    report_results: bool,
+
+    /// The format of the report written with --output
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = Format::Json)]
+    output_format: Format,
+
+    /// What separates the fields of CSV reports, with --format csv or --output-format csv
+    #[arg(long, value_enum, value_name = "DELIMITER", default_value_t = Delimiter::Comma)]
+    delimiter: Delimiter,
+
+    /// Leaves the header row out of CSV reports
+    #[arg(long)]
+    no_header: bool,
+
+    /// Terms related to where the password is used, such as the site name, that the password shouldn't resemble, can be given multiple times
+    #[arg(
+        long,
+        value_name = "STRING",
+        value_delimiter = ',',
+        env = "PASSCHECKER_CONTEXT"
+    )]
+    context: Vec<String>,
+
+    /// A term the password must neither be nor contain, whatever the case, failing it before the wordlists are scanned, can be given multiple times
+    #[arg(
+        long,
+        value_name = "WORD",
+        value_delimiter = ',',
+        env = "PASSCHECKER_BAN"
+    )]
+    ban: Vec<String>,
+
+    /// Another default password to compare the password against along with the built-in ones, such as the one of an in-house system, can be given multiple times
+    #[arg(
+        long,
+        value_name = "PASSWORD",
+        value_delimiter = ',',
+        env = "PASSCHECKER_DEFAULT_PASSWORD"
+    )]
+    default_password: Vec<String>,
+
+    /// A regular expression the password has to match, for format rules the other tests don't cover, can be given multiple times
+    #[arg(long, value_name = "PATTERN", env = "PASSCHECKER_MATCH_REGEX")]
+    match_regex: Vec<String>,
+
+    /// A regular expression the password may not match, can be given multiple times
+    #[arg(long, value_name = "PATTERN", env = "PASSCHECKER_REJECT_REGEX")]
+    reject_regex: Vec<String>,
+
+    /// The username the password belongs to, which the password shouldn't contain or resemble, even with letters swapped for look-alikes
+    #[arg(long, value_name = "NAME", env = "PASSCHECKER_USERNAME")]
+    username: Option<String>,
+
+    /// The email address the password belongs to, whose local part the password shouldn't contain or resemble, even with letters swapped for look-alikes
+    #[arg(long, value_name = "ADDRESS", env = "PASSCHECKER_EMAIL")]
+    email: Option<String>,
+
+    /// How many tests have to pass for the password to be accepted, defaults to all of the tests that weren't ignored
+    #[arg(long, value_name = "COUNT", env = "PASSCHECKER_MIN_PASSED")]
+    min_passed: Option<u8>,
+
+    /// How many milliseconds the tests of a password may take in total. The cheapest tests run first, the ones that haven't started once the time is up are skipped and reported as inconclusive
+    #[arg(long, value_name = "MILLISECONDS", env = "PASSCHECKER_MAX_TIME_MS")]
+    max_time_ms: Option<u64>,
+
+    /// Also checks whether a service would accept the password, by the password rules it documents
+    #[arg(long, value_name = "NAME", value_parser = PossibleValuesParser::new(SERVICES.iter().map(|service| service.name)), env = "PASSCHECKER_SERVICE")]
+    service: Option<String>,
+
+    /// Checks a password by its SHA-1 hash instead of its plaintext, running only the tests that work from the hash: the Pwned Passwords lookup and exact matches against the password history
+    #[arg(long, value_name = "HEX", value_parser = parse_sha1, conflicts_with_all = ["password", "batch", "repeat", "pam", "compare", "dump_matches", "confirm", "format", "output"])]
+    sha1_hash: Option<String>,
+
+    /// Keeps prompting for passwords after checking one, until an empty line or EOF, reading the wordlists only once
+    #[arg(long, conflicts_with_all = ["password", "batch", "pam"])]
+    repeat: bool,
+
+    /// Checks every line of a file as a separate password, - reads them from STDIN
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["password", "compare"])]
+    batch: Option<PathBuf>,
+
+    /// Only prints how many passwords of a --batch were accepted and how often each test failed
+    #[arg(long, requires = "batch", conflicts_with_all = ["format", "output", "check"])]
+    summary_only: bool,
+
+    /// How many passwords of a --batch are checked at the same time, defaults to the number of CPUs
+    #[arg(long, value_name = "COUNT", requires = "batch")]
+    jobs: Option<NonZeroUsize>,
+
+    /// Prints nothing and only sets the exit code: 0 if the password was accepted, 1 if it was accepted with warnings and 2 otherwise
+    #[arg(long, conflicts_with_all = ["format", "compare", "stats"])]
+    check: bool,
+
+    /// With --check, exits with 2 instead of 1 if the password was accepted with warnings
+    #[arg(long, requires = "check")]
+    warnings_as_errors: bool,
+
+    /// Asks whether to use a password that wasn't accepted anyway, exiting with 0 if the answer is yes and 2 otherwise. Only asks on a terminal and never with --quiet, the answer is then no
+    #[arg(long, conflicts_with_all = ["batch", "format", "check", "pam", "compare"])]
+    confirm: bool,
+
+    /// Warns when the password entered at the prompt arrived faster than it could have been typed, which suggests it was pasted. Only when STDIN is a terminal
+    #[arg(long, conflicts_with_all = ["password", "batch", "sha1_hash", "pam"], env = "PASSCHECKER_DETECT_PASTE")]
+    detect_paste: bool,
+
+    /// The time per character of the password under which --detect-paste takes it as pasted
+    #[arg(long, value_name = "MILLISECONDS", default_value_t = DEFAULT_PASTE_THRESHOLD_MS, requires = "detect_paste", env = "PASSCHECKER_PASTE_THRESHOLD_MS")]
+    paste_threshold_ms: u64,
+
+    /// The label printed for tests that passed
+    #[arg(long, value_name = "LABEL", default_value = "success")]
+    success_label: String,
+
+    /// The label printed for tests that passed with a warning
+    #[arg(long, value_name = "LABEL", default_value = "warning")]
+    warning_label: String,
+
+    /// The label printed for tests that failed
+    #[arg(long, value_name = "LABEL", default_value = "failure")]
+    failure_label: String,
+
+    /// The label printed for tests that were ignored or inconclusive
+    #[arg(long, value_name = "LABEL", default_value = "ignored")]
+    ignored_label: String,
+
+    /// The label printed for advisory tests that failed
+    #[arg(long, value_name = "LABEL", default_value = "advisory")]
+    advisory_label: String,
+
+    /// Masks the password, and anything in the output that could be part of it, with asterisks
+    #[arg(long)]
+    mask: bool,
+
+    /// Runs as a password quality helper for PAM: reads a single password from STDIN, prints the tests it failed on one line and exits with 0 if it was accepted and 2 otherwise
+    #[arg(long, conflicts_with_all = ["password", "batch", "compare", "format", "output", "check", "stats"])]
+    pam: bool,
+
+    /// Never colors the output, even on a terminal that supports colors
+    #[arg(long)]
+    no_color: bool,
+
+    /// Prints pure ASCII without any escape sequences, skipping color detection entirely, for terminals that claim color support but garble it. Implies --no-color, and --symbols then uses +, !, x, - and ~
+    #[arg(long)]
+    plain: bool,
+
+    /// The colors of the status labels
+    #[arg(long, value_enum, value_name = "PALETTE", default_value_t = Palette::Default)]
+    palette: Palette,
+
+    /// How many colors to use, detected from the terminal by default. Where 24-bit colors aren't supported the output falls back to 256 and then 16 colors
+    #[arg(long, value_enum, value_name = "DEPTH", default_value_t = ColorDepth::Auto)]
+    color_depth: ColorDepth,
+
+    /// Prefixes the status labels with ✓, !, ✗, – and ~, so they can be told apart without colors
+    #[arg(long)]
+    symbols: bool,
+
+    /// The weakest strength tier a password is accepted in, which then alone decides whether it is accepted and the exit code, 2 if the tier isn't reached
+    #[arg(long, value_enum, value_name = "TIER", env = "PASSCHECKER_MIN_TIER")]
+    min_tier: Option<Tier>,
+
+    /// Estimates how long guessing the password takes an online attacker against a throttled login and an offline one against a leaked fast hash
+    #[arg(long)]
+    crack_times: bool,
+
+    /// How many guesses per second the online attacker of --crack-times gets, defaults to 100
+    #[arg(long, value_name = "RATE", env = "PASSCHECKER_ONLINE_RATE")]
+    online_rate: Option<u64>,
+
+    /// How many guesses per second the offline attacker of --crack-times gets, defaults to 10000000000
+    #[arg(long, value_name = "RATE", env = "PASSCHECKER_OFFLINE_RATE")]
+    offline_rate: Option<u64>,
+
+    /// Lists the tests from the most severe, with their severity after their status
+    #[arg(long)]
+    by_severity: bool,
+
+    /// Leaves out the best match of the collision tests, only printing whether they passed. --show-match-rank brings it back
+    #[arg(long)]
+    no_match_details: bool,
+
+    /// Lists a step to fix every failed test after the results, not only the most important one
+    #[arg(long, env = "PASSCHECKER_CHECKLIST")]
+    checklist: bool,
+
+    /// Prints how long each test took to STDERR
+    #[arg(long)]
+    stats: bool,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Builds an index of a wordlist for --index, skipping comments and blank lines if --wordlist-skip-comments is given
+    BuildIndex {
+        /// The wordlist to index
+        wordlist: PathBuf,
+        /// Where to write the index
+        output: PathBuf,
+    },
+    /// Writes the internal wordlist to a file, one entry per line, to inspect it or build on it
+    ExportWordlist {
+        /// Where to write the wordlist
+        output: PathBuf,
+    },
+    /// Reports statistics about a wordlist, such as its duplicates and comments, to help curate it. Entries are filtered like --wordlist-skip-comments filters them
+    LintWordlist {
+        /// The wordlist to lint
+        wordlist: PathBuf,
+        /// Prints the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// The tests whose info is the best wordlist match, left out with --no-match-details
+const MATCH_DETAIL_TESTS: [Ignore; 4] = [
+    Ignore::WordlistCollisions,
+    Ignore::NormalizedCollisions,
+    Ignore::TransformedCollisions,
+    Ignore::Cracklib,
+];
+
+/// The time per character, in milliseconds, under which --detect-paste takes a password as pasted.
+/// Even the fastest typists take over 60 per character
+const DEFAULT_PASTE_THRESHOLD_MS: u64 = 50;
+
+/// The exit code of a password that was accepted with warnings under --check
+const EXIT_WARNINGS: u8 = 1;
+/// The exit code of a password that wasn't accepted under --check
+const EXIT_REJECTED: u8 = 2;
+
+/// How often the progress of reading the wordlists is redrawn, wordlists read faster than that are
+/// never drawn
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// The width of the progress bar, in characters
+const PROGRESS_WIDTH: usize = 30;
+/// The frames of the spinner shown for wordlists whose size isn't known
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// The progress of reading the wordlists on STDERR: a bar for plain files, whose size is known,
+/// and a spinner counting the lines read for STDIN and compressed files
+struct Indicator {
+    enabled: bool,
+    started: Instant,
+    drawn: Option<Instant>,
+    frame: usize,
 }
 
-#[derive(clap::ValueEnum, Clone, Debug, PartialEq, PartialOrd, Eq)]
-enum Ignore {
-    MinimumChars,
-    Numbers,
-    SpecialChars,
-    WordlistCollisions,
+impl Indicator {
+    fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            started: Instant::now(),
+            drawn: None,
+            frame: 0,
+        }
+    }
+
+    fn update(&mut self, path: &Path, progress: compression::Progress) {
+        let now = Instant::now();
+        if !self.enabled || now - self.drawn.unwrap_or(self.started) < PROGRESS_INTERVAL {
+            return;
+        }
+        self.drawn = Some(now);
+        let name = if path == Path::new("-") {
+            Cow::Borrowed("STDIN")
+        } else {
+            path.to_string_lossy()
+        };
+        let line = match progress.total {
+            Some(total) if total > 0 => {
+                let share = (progress.bytes as f64 / total as f64).min(1.0);
+                let filled = (share * PROGRESS_WIDTH as f64) as usize;
+                format!(
+                    "[{}{}] {:>3.0}% of {name}",
+                    "#".repeat(filled),
+                    "-".repeat(PROGRESS_WIDTH - filled),
+                    share * 100.0
+                )
+            }
+            _ => {
+                self.frame = (self.frame + 1) % SPINNER.len();
+                format!("{} {} lines of {name}", SPINNER[self.frame], progress.lines)
+            }
+        };
+        eprint!("\r\x1b[K{line}");
+    }
+
+    /// Clears the indicator once the wordlists are read
+    fn finish(&self) {
+        if self.drawn.is_some() {
+            eprint!("\r\x1b[K");
+        }
+    }
 }
 
-const DEFAULT_MIN_LENGTH: u8 = 8;
+/// The files the results are written to besides STDOUT, opened once for a whole batch
+struct Files {
+    /// The report from --output
+    output: Option<File>,
+    /// The audit log from --report-file
+    audit: Option<File>,
+}
+
+/// The width of a test name once printed, before its figures are highlighted
+fn display_width(name: &str) -> usize {
+    name.chars().count()
+}
+
+/// Styles the human readable output. The default renderer colors it if the stream it is written
+/// to supports colors, the plain one never does, whatever the terminal and the environment
+struct Renderer {
+    success: Style,
+    warning: Style,
+    failure: Style,
+    ignored: Style,
+    /// Failures of advisory tests
+    advisory_failure: Style,
+    advisory: Style,
+    /// The password itself
+    password: Style,
+    /// Counts, such as the number of tests passed
+    highlight: Style,
+    /// Percentages and durations
+    figure: Style,
+    /// The severities of --by-severity, from info to critical
+    severities: [Style; 5],
+    plain: bool,
+    /// Whether statuses are prefixed with a symbol, so they can be told apart without colors
+    symbols: bool,
+    /// Whether the symbols are ASCII ones
+    ascii: bool,
+}
+
+/// The colors of the status labels
+#[derive(clap::ValueEnum, Clone, Copy, Default)]
+enum Palette {
+    /// Green, yellow, red and white
+    #[default]
+    Default,
+    /// Blue, magenta, yellow and white, which stay distinct with the common kinds of color blindness
+    Accessible,
+}
+
+/// How many colors the terminal can show. The statuses and severities get subtler shades with
+/// 256 colors and exact ones with 24-bit colors, and fall back to the 16 basic colors elsewhere
+#[derive(clap::ValueEnum, Clone, Copy, Default)]
+enum ColorDepth {
+    /// Detected from the terminal and the environment, such as COLORTERM=truecolor
+    #[default]
+    Auto,
+    /// The 16 basic colors every color terminal supports
+    #[value(name = "16")]
+    Basic,
+    /// The 256 colors of xterm
+    #[value(name = "256")]
+    Xterm,
+    /// 24-bit colors
+    Truecolor,
+}
+
+impl ColorDepth {
+    /// The depth, detected from STDOUT if it is `Auto`
+    fn resolve(self) -> Self {
+        match self {
+            ColorDepth::Auto => match supports_color::on_cached(Stdout) {
+                Some(level) if level.has_16m => ColorDepth::Truecolor,
+                Some(level) if level.has_256 => ColorDepth::Xterm,
+                _ => ColorDepth::Basic,
+            },
+            depth => depth,
+        }
+    }
+
+    /// The one of `basic`, `xterm` and `rgb` the depth can show
+    fn pick(self, basic: AnsiColors, xterm: XtermColors, (r, g, b): (u8, u8, u8)) -> DynColors {
+        match self {
+            ColorDepth::Truecolor => DynColors::Rgb(r, g, b),
+            ColorDepth::Xterm => DynColors::Xterm(xterm),
+            ColorDepth::Auto | ColorDepth::Basic => DynColors::Ansi(basic),
+        }
+    }
+}
 
-struct Test<'a> {
-    name: String,
-    test: fn(&'a Cli, &str) -> (Option<bool>, Cow<'a, str>),
-    ignore: Ignore,
+impl Default for Renderer {
+    fn default() -> Self {
+        Self::new(Palette::Default, ColorDepth::Basic)
+    }
 }
 
-impl<'a> Test<'a> {
-    fn new(
-        name: String,
-        test: fn(&'a Cli, &str) -> (Option<bool>, Cow<'a, str>),
-        ignore: Ignore,
-    ) -> Self {
-        Self { name, test, ignore }
+impl Renderer {
+    /// A colored renderer using `palette` for the statuses, in the colors `depth` can show
+    fn new(palette: Palette, depth: ColorDepth) -> Self {
+        let depth = depth.resolve();
+        let background = |basic, xterm, rgb| {
+            Style::new()
+                .black()
+                .bold()
+                .on_color(depth.pick(basic, xterm, rgb))
+        };
+        let foreground = |basic, xterm, rgb| Style::new().color(depth.pick(basic, xterm, rgb));
+        let renderer = Self {
+            success: background(
+                AnsiColors::BrightGreen,
+                XtermColors::PastelGreen,
+                (87, 204, 122),
+            ),
+            warning: background(
+                AnsiColors::BrightYellow,
+                XtermColors::Dandelion,
+                (250, 208, 92),
+            ),
+            failure: background(
+                AnsiColors::BrightRed,
+                XtermColors::BittersweetOrange,
+                (242, 92, 92),
+            ),
+            ignored: background(AnsiColors::White, XtermColors::DarkSilver, (191, 191, 191)),
+            advisory_failure: background(
+                AnsiColors::BrightCyan,
+                XtermColors::MalibuBlue,
+                (102, 209, 242),
+            ),
+            advisory: foreground(AnsiColors::Yellow, XtermColors::YellowSea, (245, 176, 33)).bold(),
+            password: foreground(
+                AnsiColors::Blue,
+                XtermColors::CornflowerBlue,
+                (99, 142, 250),
+            )
+            .bold(),
+            highlight: foreground(
+                AnsiColors::Blue,
+                XtermColors::CornflowerBlue,
+                (99, 142, 250),
+            ),
+            figure: foreground(AnsiColors::Yellow, XtermColors::YellowSea, (245, 176, 33)),
+            severities: [
+                foreground(
+                    AnsiColors::White,
+                    XtermColors::DarkSilverChalice,
+                    (170, 170, 170),
+                ),
+                foreground(
+                    AnsiColors::Cyan,
+                    XtermColors::ShakespeareBlue,
+                    (97, 175, 214),
+                ),
+                foreground(AnsiColors::Yellow, XtermColors::Dandelion, (250, 208, 92)),
+                foreground(
+                    AnsiColors::BrightRed,
+                    XtermColors::FlushOrange,
+                    (250, 137, 22),
+                ),
+                foreground(
+                    AnsiColors::Red,
+                    XtermColors::BittersweetOrange,
+                    (242, 92, 92),
+                )
+                .bold(),
+            ],
+            plain: false,
+            symbols: false,
+            ascii: false,
+        };
+        match palette {
+            Palette::Default => renderer,
+            Palette::Accessible => Self {
+                success: Style::new().white().bold().on_color(depth.pick(
+                    AnsiColors::Blue,
+                    XtermColors::EndeavourBlue,
+                    (0, 114, 178),
+                )),
+                warning: background(
+                    AnsiColors::BrightMagenta,
+                    XtermColors::CanCanPink,
+                    (204, 121, 167),
+                ),
+                failure: background(AnsiColors::BrightYellow, XtermColors::Gold, (240, 228, 66)),
+                ..renderer
+            },
+        }
+    }
+
+    /// A renderer that leaves all text unstyled
+    fn plain() -> Self {
+        Self {
+            plain: true,
+            ..Self::default()
+        }
+    }
+
+    /// `text` in `style`, unless the renderer is plain or `stream` doesn't support colors
+    fn paint(&self, stream: Stream, text: impl Display, style: Style) -> String {
+        if self.plain {
+            text.to_string()
+        } else {
+            text.if_supports_color(stream, |x| x.style(style))
+                .to_string()
+        }
+    }
+
+    /// The status `label` of a test with `outcome`, prefixed with its symbol if enabled
+    fn status(&self, outcome: Outcome, label: &str) -> String {
+        let (symbol, ascii_symbol, style) = match outcome {
+            Outcome::Success => ("✓", "+", self.success),
+            Outcome::Warning => ("!", "!", self.warning),
+            Outcome::Failure => ("✗", "x", self.failure),
+            Outcome::Ignored => ("–", "-", self.ignored),
+            Outcome::Advisory => ("~", "~", self.advisory_failure),
+        };
+        let symbol = if self.ascii { ascii_symbol } else { symbol };
+        if self.symbols {
+            self.paint(Stdout, format!("{symbol} {label}"), style)
+        } else {
+            self.paint(Stdout, label, style)
+        }
+    }
+
+    /// The name of a test with its figures highlighted, such as the 8 of "At least 8 characters"
+    fn name(&self, stream: Stream, name: &str) -> String {
+        let mut painted = String::with_capacity(name.len());
+        let mut rest = name;
+        while let Some(start) = rest.find(|ch: char| ch.is_ascii_digit()) {
+            let end = rest[start..]
+                .find(|ch: char| !ch.is_ascii_digit())
+                .map_or(rest.len(), |end| start + end);
+            painted += &rest[..start];
+            painted += &self.paint(stream, &rest[start..end], self.highlight);
+            rest = &rest[end..];
+        }
+        painted + rest
     }
 }
 
-// #[derive(Subcommand)]
-// enum Commands {
-//     /// does testing things
-//     Test {
-//         /// lists test values
-//         #[arg(short, long)]
-//         list: bool,
-//     },
-// }
-
-fn main() {
-    let success_style: Style = Style::new().black().bold().on_bright_green();
-    let failure_style: Style = Style::new().black().bold().on_bright_red();
-    let ignored_style: Style = Style::new().black().bold().on_white();
+fn main() -> ExitCode {
     let cli = Cli::parse();
-    let mut buf = String::with_capacity(8);
+    let mut renderer = if cli.plain {
+        Renderer {
+            ascii: true,
+            ..Renderer::plain()
+        }
+    } else if cli.no_color {
+        Renderer::plain()
+    } else {
+        Renderer::new(cli.palette, cli.color_depth)
+    };
+    renderer.symbols = cli.symbols;
+    let config = match cli.policy_json {
+        Some(ref json) => Some(Config::from_json(json)),
+        None => cli.config.as_deref().map(Config::load),
+    };
+    let mut config = match config {
+        Some(Ok(config)) => config,
+        Some(Err(err)) => {
+            eprintln!("{}", renderer.paint(Stderr, &err, renderer.failure));
+            return ExitCode::FAILURE;
+        }
+        None => Config::default(),
+    };
+    if cli.profile.is_some() {
+        config.profile = cli.profile
+    }
+    let mut policy = Policy::from(config);
+    if let Some(min_length) = cli.min_length {
+        policy.min_length = min_length
+    }
+    if let Some(max_length) = cli.max_length {
+        policy.max_length = Some(max_length)
+    }
+    if let Some(similarity) = cli.similarity {
+        policy.similarity = similarity
+    }
+    if let Some(warn_similarity) = cli.warn_similarity {
+        policy.warn_similarity = Some(warn_similarity)
+    }
+    if let Some(match_display_threshold) = cli.match_display_threshold {
+        policy.match_display_threshold = match_display_threshold
+    }
+    if cli.report_threshold {
+        policy.report_threshold = true
+    }
+    if let Some(ref normalizations) = cli.normalizations {
+        policy.normalizations = normalizations.clone()
+    }
+    if let Some(max_character_share) = cli.max_character_share {
+        policy.max_character_share = max_character_share
+    }
+    if let Some(min_unique) = cli.min_unique {
+        policy.min_unique = Some(min_unique)
+    }
+    if let Some(compressibility_min_length) = cli.compressibility_min_length {
+        policy.compressibility_min_length = compressibility_min_length
+    }
+    if let Some(min_compression_ratio) = cli.min_compression_ratio {
+        policy.min_compression_ratio = min_compression_ratio
+    }
+    if let Some(max_dictionary_share) = cli.max_dictionary_share {
+        policy.max_dictionary_share = max_dictionary_share
+    }
+    if cli.show_match_location {
+        policy.show_match_location = true
+    }
+    if let Some(algorithm) = cli.algorithm {
+        policy.algorithm = algorithm
+    }
+    if let Some(tie_break) = cli.tie_break {
+        policy.tie_break = tie_break
+    }
+    if cli.show_match_rank {
+        policy.show_match_rank = true
+    }
+    if let Some(fail_top) = cli.fail_top {
+        policy.fail_top = Some(fail_top)
+    }
+    if cli.wordlist_stop_on_hit {
+        policy.wordlist_stop_on_hit = true
+    }
+    if let Some(min_log10_guesses) = cli.min_log10_guesses {
+        policy.min_log10_guesses = min_log10_guesses
+    }
+    if cli.ngram_entropy {
+        policy.ngram_entropy = true
+    }
+    if let Some(min_ngram_bits) = cli.min_ngram_bits {
+        policy.min_ngram_bits = min_ngram_bits
+    }
+    if !cli.wordlist.is_empty() {
+        policy.wordlists = cli.wordlist.clone()
+    }
+    if cli.wordlist_skip_comments {
+        policy.skip_comments = true
+    }
+    if cli.dedup_wordlist {
+        policy.dedup_wordlist = true
+    }
+    if let Some(wordlist_min_length) = cli.wordlist_min_length {
+        policy.wordlist_min_length = wordlist_min_length
+    }
+    if let Some(ref comment_prefix) = cli.comment_prefix {
+        policy.comment_prefix = comment_prefix.clone()
+    }
+    if let Some(ref ignore) = cli.ignore {
+        policy.ignore = ignore.clone()
+    }
+    if let Some(ref advisory) = cli.advisory {
+        policy.advisory = advisory.clone()
+    }
+    if cli.passphrase {
+        policy.passphrase = true
+    }
+    if cli.ascii_only {
+        policy.ascii_only = true
+    }
+    if let Some(min_words) = cli.min_words {
+        policy.min_words = min_words
+    }
+    if cli.substrings {
+        policy.substrings = true
+    }
+    if cli.concatenated_words {
+        policy.concatenated_words = true
+    }
+    if cli.word_pairs {
+        policy.word_pairs = true
+    }
+    if let Some(ref transformations) = cli.transformations {
+        policy.transformations = transformations.clone()
+    }
+    if cli.leet_collisions {
+        policy.leet_collisions = true
+    }
+    if cli.check_confusables {
+        policy.confusables = true
+    }
+    if cli.cracklib {
+        policy.cracklib = true
+    }
+    if let Some(ref breach_counts) = cli.breach_counts {
+        policy.breach_counts = Some(breach_counts.clone())
+    }
+    if let Some(count_format) = cli.count_format {
+        policy.count_format = count_format
+    }
+    if let Some(breach_threshold) = cli.breach_threshold {
+        policy.breach_threshold = breach_threshold
+    }
+    if let Some(ref cracklib_dict) = cli.cracklib_dict {
+        policy.cracklib = true;
+        policy.cracklib_dictionary = Some(cracklib_dict.clone())
+    }
+    if let Some(substring_min_length) = cli.substring_min_length {
+        policy.substring_min_length = substring_min_length
+    }
+    if !cli.context.is_empty() {
+        policy.context = cli.context.clone()
+    }
+    if !cli.ban.is_empty() {
+        policy.banned = cli.ban.clone()
+    }
+    if !cli.default_password.is_empty() {
+        policy.extra_default_passwords = cli.default_password.clone()
+    }
+    if !cli.match_regex.is_empty() {
+        policy.match_regex = cli.match_regex.clone()
+    }
+    if !cli.reject_regex.is_empty() {
+        policy.reject_regex = cli.reject_regex.clone()
+    }
+    if let Some(ref username) = cli.username {
+        policy.username = Some(username.clone())
+    }
+    if let Some(ref email) = cli.email {
+        policy.email = Some(email.clone())
+    }
+    if let Some(ref allowlist) = cli.allowlist {
+        policy.allowlist = Some(allowlist.clone())
+    }
+    if let Some(ref pwned_dir) = cli.pwned_dir {
+        policy.pwned_dir = Some(pwned_dir.clone())
+    }
+    if let Some(ref history) = cli.history {
+        policy.history = Some(history.clone())
+    }
+    if let Some(ref index) = cli.index {
+        policy.index = Some(index.clone())
+    }
+    if let Some(min_passed) = cli.min_passed {
+        policy.min_passed = Some(min_passed)
+    }
+    if let Some(min_tier) = cli.min_tier {
+        policy.min_tier = Some(min_tier)
+    }
+    if let Some(online_rate) = cli.online_rate {
+        policy.online_rate = online_rate
+    }
+    if let Some(offline_rate) = cli.offline_rate {
+        policy.offline_rate = offline_rate
+    }
+    if let Some(max_time_ms) = cli.max_time_ms {
+        policy.max_time_ms = Some(max_time_ms)
+    }
+    if let Some(ref service) = cli.service {
+        policy.service = Some(service.clone())
+    }
+    if cli.print_config {
+        match toml::to_string(&Config::from(&policy)) {
+            Ok(config) => print!("{config}"),
+            Err(err) => {
+                eprintln!(
+                    "{}",
+                    renderer.paint(
+                        Stderr,
+                        format!("Failed to serialize the config: {err}"),
+                        renderer.failure
+                    )
+                );
+                return ExitCode::FAILURE;
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+    if let Some(Commands::BuildIndex {
+        ref wordlist,
+        ref output,
+    }) = cli.command
+    {
+        return build_index(&policy, &renderer, wordlist, output);
+    }
+    if let Some(Commands::ExportWordlist { ref output }) = cli.command {
+        return export_wordlist(&renderer, output);
+    }
+    if let Some(Commands::LintWordlist { ref wordlist, json }) = cli.command {
+        return lint_wordlist(&policy, &renderer, wordlist, json);
+    }
+    let stdin_wordlists = policy
+        .wordlists
+        .iter()
+        .filter(|path| path.as_path() == Path::new("-"))
+        .count();
+    let stdin_passwords = match cli.batch {
+        Some(ref batch) => batch == Path::new("-"),
+        None => cli.password.is_none(),
+    };
+    if stdin_wordlists > 1 || (stdin_wordlists == 1 && stdin_passwords) {
+        eprintln!(
+            "{}",
+            renderer.paint(Stderr, "Only one of the wordlist and the passwords can be read from STDIN, give the password as an argument when using --wordlist -. Aborting.", renderer.failure)
+        );
+        return ExitCode::FAILURE;
+    }
+    let mut indicator = Indicator::new(!cli.quiet && !cli.check && stderr().is_terminal());
+    let checker = Checker::with_progress(policy, |path, progress| indicator.update(path, progress));
+    indicator.finish();
+    let mut checker = match checker {
+        Ok(checker) => checker,
+        Err(err) => {
+            eprintln!("{}", renderer.paint(Stderr, &err, renderer.failure));
+            return ExitCode::FAILURE;
+        }
+    };
+    checker.mask = cli.mask;
+    if cli.pam {
+        return pam_check(&checker).into();
+    }
+    let policy = &checker.policy;
+    if cfg!(feature = "embedded-wordlist")
+        && policy.wordlists.is_empty()
+        && !policy.ignore.contains(&Ignore::WordlistCollisions)
+        && !cli.quiet
+        && !cli.check
+        && !cli.compare
+    {
+        eprintln!(
+            "{}",
+            renderer.paint(Stderr, "Advisory: the internal wordlist only contains the 10k most common passwords, use --wordlist with a larger list for real security assessments.", renderer.advisory)
+        );
+    }
+    if policy.dedup_wordlist && !cli.quiet && !cli.check {
+        eprintln!(
+            "{}",
+            renderer.paint(
+                Stderr,
+                format!(
+                    "Removed {} duplicate wordlist entries.",
+                    checker.duplicates_removed
+                ),
+                renderer.advisory
+            )
+        );
+    }
+    if policy.wordlist_min_length > 0 && !cli.quiet && !cli.check {
+        eprintln!(
+            "{}",
+            renderer.paint(
+                Stderr,
+                format!(
+                    "Skipped {} wordlist entries shorter than {} characters.",
+                    checker.short_entries, policy.wordlist_min_length
+                ),
+                renderer.advisory
+            )
+        );
+    }
+    if !cli.quiet && !cli.check {
+        for warning in policy.warnings() {
+            eprintln!(
+                "{}",
+                renderer.paint(Stderr, format!("Warning: {warning}."), renderer.advisory)
+            );
+        }
+    }
+    let output = match cli.output {
+        Some(ref path) if !cli.compare => match File::create(path) {
+            Ok(file) => Some(file),
+            Err(_) => {
+                eprintln!(
+                    "{}",
+                    renderer.paint(
+                        Stderr,
+                        format!("Failed to write report to '{}'.", path.display()),
+                        renderer.failure
+                    )
+                );
+                None
+            }
+        },
+        _ => None,
+    };
+    let audit = match cli.report_file {
+        Some(ref path) => match OpenOptions::new().create(true).append(true).open(path) {
+            Ok(file) => Some(file),
+            Err(err) => {
+                eprintln!(
+                    "{}",
+                    renderer.paint(
+                        Stderr,
+                        format!("Failed to open report file '{}': {err}", path.display()),
+                        renderer.failure
+                    )
+                );
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+    let mut files = Files { output, audit };
 
-    let password = if let Some(ref password) = cli.password {
-        password
+    if let Some(ref hash) = cli.sha1_hash {
+        let results = || checker.check_sha1(hash);
+        return check_password(&cli, &checker, &renderer, hash, false, results, &mut files).into();
+    }
+    let Some(ref batch) = cli.batch else {
+        if let Some(ref password) = cli.password {
+            let results = || checker.check(password);
+            return check_password(
+                &cli, &checker, &renderer, password, false, results, &mut files,
+            )
+            .into();
+        }
+        // The exit code of the worst password when prompting with --repeat
+        let mut exit_code = 0;
+        // Piped input always arrives at once
+        let detect_paste = cli.detect_paste && stdin().is_terminal();
+        if cli.repeat {
+            start_streams(&cli, &renderer, &mut files);
+        }
+        // The wordlists are only read once, however many passwords are checked
+        loop {
+            let mut buf = String::with_capacity(8);
+            if !cli.check {
+                let mut lock = stdout().lock();
+                write!(lock, "Please enter the password to check.\n> ")
+                    .expect("Failed to write to stdout");
+                stdout().flush().expect("Failed to flust stdout");
+            }
+            let stdin = stdin();
+            let prompted = Instant::now();
+            // If no password was provided as an argument
+            let Ok(_) = stdin.read_line(&mut buf) else {
+                eprintln!("{}",renderer.paint(Stderr, "No password provided as argument and failed to read password from STDIN. Aborting.", renderer.failure));
+                return ExitCode::FAILURE;
+            };
+            match buf.pop() {
+                Some('\n') => (),
+                Some(ch) => buf.push(ch),
+                // Reached EOF without reading anything
+                None => (),
+            }
+            // An empty line or EOF ends the prompts
+            if cli.repeat && buf.is_empty() {
+                break;
+            }
+            if detect_paste
+                && !cli.check
+                && pasted(&buf, prompted.elapsed(), cli.paste_threshold_ms)
+            {
+                eprintln!(
+                    "{}",
+                    renderer.paint(
+                        Stderr,
+                        "The password arrived faster than it could have been typed, it looks pasted.",
+                        renderer.warning
+                    )
+                );
+            }
+            let results = || checker.check(&buf);
+            let code = check_password(
+                &cli, &checker, &renderer, &buf, cli.repeat, results, &mut files,
+            );
+            if !cli.repeat {
+                return code.into();
+            }
+            exit_code = exit_code.max(code);
+            if cli.format.is_none() && !cli.check {
+                println!();
+            }
+        }
+        end_streams(&cli, &renderer, &mut files);
+        return exit_code.into();
+    };
+    let passwords = if batch == Path::new("-") {
+        std::io::read_to_string(stdin())
     } else {
-        let mut lock = stdout().lock();
-        write!(lock, "Please enter the password to check.\n> ").expect("Failed to write to stdout");
-        stdout().flush().expect("Failed to flust stdout");
-        let stdin = stdin();
-        // If no password was provided as an argument
-        let Ok(_) = stdin.read_line(&mut buf) else {
-            eprintln!("{}","No password provided as argument and failed to read password from STDIN. Aborting.".if_supports_color(Stderr, |x|x.style(failure_style)));
-            return
+        read_to_string(batch)
+    };
+    let Ok(passwords) = passwords else {
+        eprintln!(
+            "{}",
+            renderer.paint(
+                Stderr,
+                format!("Failed to read passwords from '{}'.", batch.display()),
+                renderer.failure
+            )
+        );
+        return ExitCode::FAILURE;
+    };
+    let human = cli.format.is_none() && !cli.check;
+    // The exit code of the worst password
+    let mut exit_code = 0;
+    // Blank lines, such as the one a file usually ends with, aren't passwords
+    let passwords: Vec<&str> = passwords.lines().filter(|line| !line.is_empty()).collect();
+    let jobs = cli
+        .jobs
+        .or_else(|| thread::available_parallelism().ok())
+        .map_or(1, NonZeroUsize::get);
+    if cli.summary_only {
+        print_summary(&checker, &renderer, &passwords, jobs);
+        return ExitCode::SUCCESS;
+    }
+    if !passwords.is_empty() {
+        start_streams(&cli, &renderer, &mut files);
+    }
+    checker.check_batch(&passwords, jobs, |index, results| {
+        if human && index > 0 {
+            println!();
+        }
+        let password = passwords[index];
+        let results = || results;
+        let code = check_password(
+            &cli, &checker, &renderer, password, true, results, &mut files,
+        );
+        exit_code = exit_code.max(code);
+    });
+    // Streams that need closing, such as JUnit XML, are closed once every password was reported
+    if !passwords.is_empty() {
+        end_streams(&cli, &renderer, &mut files);
+    }
+    exit_code.into()
+}
+
+/// Writes an index of `wordlist` to `output` for --index
+fn build_index(policy: &Policy, renderer: &Renderer, wordlist: &Path, output: &Path) -> ExitCode {
+    let contents = match compression::read_to_string(wordlist) {
+        Ok(contents) => ExternalWordlist(contents),
+        Err(err) => {
+            eprintln!(
+                "{}",
+                renderer.paint(
+                    Stderr,
+                    format!("Failed to read wordlist '{}': {err}", wordlist.display()),
+                    renderer.failure
+                )
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    match index::build(&contents.filtered_entries(policy), wordlist, output) {
+        Ok(count) => {
+            println!(
+                "Indexed {} entries of '{}' into '{}'",
+                renderer.paint(Stdout, count, renderer.highlight),
+                wordlist.display(),
+                output.display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!(
+                "{}",
+                renderer.paint(
+                    Stderr,
+                    format!("Failed to write index '{}': {err}", output.display()),
+                    renderer.failure
+                )
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Writes the internal wordlist to `output`, with `\n` line endings whatever the embedded file uses
+fn export_wordlist(renderer: &Renderer, output: &Path) -> ExitCode {
+    #[cfg(feature = "embedded-wordlist")]
+    let entries = passchecker::WORDLIST.entries();
+    #[cfg(not(feature = "embedded-wordlist"))]
+    let entries: Vec<&str> = Vec::new();
+    if entries.is_empty() {
+        eprintln!(
+            "{}",
+            renderer.paint(
+                Stderr,
+                "This build of passchecker has no internal wordlist",
+                renderer.failure
+            )
+        );
+        return ExitCode::FAILURE;
+    }
+    let mut contents = entries.join("\n");
+    contents.push('\n');
+    match std::fs::write(output, contents) {
+        Ok(()) => {
+            println!(
+                "Exported {} entries of the internal wordlist into '{}'",
+                renderer.paint(Stdout, entries.len(), renderer.highlight),
+                output.display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!(
+                "{}",
+                renderer.paint(
+                    Stderr,
+                    format!("Failed to write wordlist '{}': {err}", output.display()),
+                    renderer.failure
+                )
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints the statistics of `wordlist`, as JSON if `json` is set
+fn lint_wordlist(policy: &Policy, renderer: &Renderer, wordlist: &Path, json: bool) -> ExitCode {
+    let bytes = match compression::read_bytes(wordlist) {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            eprintln!(
+                "{}",
+                renderer.paint(
+                    Stderr,
+                    format!("Failed to read wordlist '{}': {err}", wordlist.display()),
+                    renderer.failure
+                )
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+    let lint = Lint::new(&bytes, policy);
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&lint).expect("Failed to serialize the statistics")
+        );
+        return ExitCode::SUCCESS;
+    }
+    let count = |count: usize| renderer.paint(Stdout, count, renderer.highlight);
+    // Problems a curated wordlist shouldn't have are highlighted as such
+    let problem = |count: usize| {
+        let style = if count > 0 {
+            renderer.advisory
+        } else {
+            renderer.highlight
         };
-        match buf.pop() {
-            Some('\n') => (),
-            Some(ch) => buf.push(ch),
-            None => unreachable!("Somehow managed to read a 0 bytes long string from STDIN"),
+        renderer.paint(Stdout, count, style)
+    };
+    let skipped = if policy.skip_comments {
+        "skipped"
+    } else {
+        "kept as entries, skip them with --wordlist-skip-comments"
+    };
+    println!("Wordlist:       {}", wordlist.display());
+    println!("Lines:          {}", count(lint.lines));
+    println!("Entries:        {}", count(lint.entries));
+    println!("Unique entries: {}", count(lint.unique));
+    println!("Duplicates:     {}", problem(lint.duplicates));
+    println!("Blank lines:    {} ({skipped})", problem(lint.blank));
+    println!("Comment lines:  {} ({skipped})", problem(lint.comments));
+    println!("Invalid UTF-8:  {} lines", problem(lint.invalid_utf8));
+    println!(
+        "Average length: {} characters",
+        renderer.paint(
+            Stdout,
+            format!("{:.1}", lint.average_length),
+            renderer.figure
+        )
+    );
+    // Quoted, so blank entries and surrounding whitespace show
+    let sample = |entries: &[String]| {
+        let entries: Vec<String> = entries.iter().map(|entry| format!("{entry:?}")).collect();
+        entries.join(", ")
+    };
+    println!("Most common:    {}", sample(&lint.most_common));
+    println!("Least common:   {}", sample(&lint.least_common));
+    ExitCode::SUCCESS
+}
+
+/// Checks the password on STDIN the way the PAM helper contract in the README describes, returning
+/// the code the program should exit with
+fn pam_check(checker: &Checker) -> u8 {
+    let Ok(mut password) = std::io::read_to_string(stdin()) else {
+        println!("BAD PASSWORD: failed to read the password");
+        return EXIT_REJECTED;
+    };
+    // pam_exec terminates the password with a NUL, shell pipelines with a newline
+    if password.ends_with('\0') {
+        password.pop();
+    }
+    if password.ends_with('\n') {
+        password.pop();
+        if password.ends_with('\r') {
+            password.pop();
+        }
+    }
+    if password.trim().is_empty() {
+        println!("BAD PASSWORD: the password is empty");
+        return EXIT_REJECTED;
+    }
+    if checker.is_allowlisted(&password) {
+        return 0;
+    }
+    let results = checker.check(&password);
+    if checker.accepts(&password, &results) {
+        return 0;
+    }
+    if let Some(min_tier) = checker.policy.min_tier {
+        let tier = checker.strength(&password).1;
+        println!(
+            "BAD PASSWORD: {} is weaker than the required {}",
+            tier.name(),
+            min_tier.name()
+        );
+        return EXIT_REJECTED;
+    }
+    // Only the names of the tests are printed, the additional info can contain parts of the
+    // password
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|x| x.outcome == Outcome::Failure)
+        .map(|x| x.name.as_str())
+        .collect();
+    println!("BAD PASSWORD: failed {}", failed.join(", "));
+    EXIT_REJECTED
+}
+
+/// Checks every password of a batch and only prints how many were accepted and how often each test
+/// failed
+fn print_summary(checker: &Checker, renderer: &Renderer, passwords: &[&str], jobs: usize) {
+    let mut accepted = 0;
+    // How often each test failed, in the order the tests are run
+    let mut failures: Vec<(String, usize)> = Vec::new();
+    checker.check_batch(passwords, jobs, |index, results| {
+        if checker.is_allowlisted(passwords[index]) {
+            accepted += 1;
+            return;
+        }
+        if checker.accepts(passwords[index], &results) {
+            accepted += 1;
+        }
+        for result in results {
+            let failed = usize::from(matches!(
+                result.outcome,
+                Outcome::Failure | Outcome::Advisory
+            ));
+            match failures.iter_mut().find(|(name, _)| *name == result.name) {
+                Some((_, count)) => *count += failed,
+                None => failures.push((result.name, failed)),
+            }
+        }
+    });
+    println!(
+        "Accepted {} out of {} passwords ({}%)",
+        renderer.paint(Stdout, accepted, renderer.highlight),
+        renderer.paint(Stdout, passwords.len(), renderer.highlight),
+        renderer.paint(
+            Stdout,
+            format!(
+                "{:.1}",
+                accepted as f64 / passwords.len().max(1) as f64 * 100.0
+            ),
+            renderer.figure
+        )
+    );
+    let longest_name = failures
+        .iter()
+        .fold(0, |acc, (name, _)| display_width(name).max(acc))
+        + 4;
+    println!("Failures per test:");
+    for (name, count) in failures {
+        let difference = longest_name - display_width(&name);
+        let style = if count > 0 {
+            renderer.failure
+        } else {
+            renderer.success
+        };
+        println!(
+            "{name}:{}{}",
+            " ".repeat(difference),
+            renderer.paint(Stdout, count, style)
+        );
+    }
+}
+
+/// Whether `password`, entered `elapsed` after the prompt, took less than `threshold_ms` per
+/// character
+fn pasted(password: &str, elapsed: Duration, threshold_ms: u64) -> bool {
+    let chars = password.chars().count() as u64;
+    chars > 0 && elapsed < Duration::from_millis(threshold_ms.saturating_mul(chars))
+}
+
+/// Asks on the terminal whether to use a password that wasn't accepted anyway, false if there is no
+/// terminal to ask on
+fn confirmed(cli: &Cli) -> bool {
+    if cli.quiet || !stdin().is_terminal() || !stdout().is_terminal() {
+        return false;
+    }
+    print!("This password is weak; proceed anyway? [y/N] ");
+    stdout().flush().expect("Failed to flush stdout");
+    let mut answer = String::new();
+    if stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Writes the report of `password` with `results` in --format to STDOUT and to the --output file,
+/// if either is set. Reports that are `streamed` are the lines of a stream started with
+/// [`start_streams`]
+fn write_reports(
+    cli: &Cli,
+    checker: &Checker,
+    renderer: &Renderer,
+    password: &str,
+    results: &[TestResult],
+    streamed: bool,
+    files: &mut Files,
+) {
+    if cli.format.is_none() && files.output.is_none() {
+        return;
+    }
+    let (log10_guesses, tier) = checker.strength(password);
+    let shown = checker.shown(password);
+    let crack_times = checker.policy.crack_times(log10_guesses);
+    let report = Report::new(&shown, results, tier, crack_times);
+    let render = |format| {
+        if streamed {
+            report.render_line(format, cli.delimiter)
+        } else {
+            report.render(format, csv(cli))
         }
-        &buf
     };
+    if let Some(format) = cli.format {
+        println!("{}", render(format).trim_end());
+        stdout().flush().expect("Failed to flush stdout");
+    }
+    if let Some(file) = &mut files.output {
+        if write!(file, "{}", render(cli.output_format)).is_err() {
+            report_write_error(cli, renderer);
+        }
+    }
+}
 
-    let tests = [
-        Test::new(
+/// How CSV reports are written, by --delimiter and --no-header
+fn csv(cli: &Cli) -> Csv {
+    Csv {
+        delimiter: cli.delimiter,
+        header: !cli.no_header,
+    }
+}
+
+/// Reports that the --output file couldn't be written to
+fn report_write_error(cli: &Cli, renderer: &Renderer) {
+    eprintln!(
+        "{}",
+        renderer.paint(
+            Stderr,
             format!(
-                "At least {} characters",
-                cli.min_length
-                    .unwrap_or(DEFAULT_MIN_LENGTH)
-                    .if_supports_color(Stdout, |x| x.blue())
+                "Failed to write report to '{}'.",
+                cli.output.as_deref().unwrap_or(Path::new("")).display()
             ),
-            |cli: &Cli, pass: &str| {
-                let min_length = if let Some(override_length) = cli.min_length {
-                    override_length
-                } else {
-                    DEFAULT_MIN_LENGTH
-                };
-                let len = pass.len();
-                let outcome = len >= min_length.into();
-                (
-                    Some(outcome),
-                    if outcome {
-                        Cow::Borrowed("")
-                    } else {
-                        Cow::Owned(format!(
-                            "Password too short: {}/{} characters",
-                            len, min_length
-                        ))
-                    },
+            renderer.failure
+        )
+    );
+}
+
+/// Starts the streams of reports of --batch and --repeat on STDOUT and in the --output file, with
+/// the header of their format, before any password is checked
+fn start_streams(cli: &Cli, renderer: &Renderer, files: &mut Files) {
+    if let Some(format) = cli.format {
+        print!("{}", format.header(csv(cli)));
+    }
+    if let Some(file) = &mut files.output {
+        if write!(file, "{}", cli.output_format.header(csv(cli))).is_err() {
+            report_write_error(cli, renderer);
+        }
+    }
+}
+
+/// Ends the streams of reports started with [`start_streams`], closing the root element of
+/// JUnit XML
+fn end_streams(cli: &Cli, renderer: &Renderer, files: &mut Files) {
+    if let Some(format) = cli.format {
+        print!("{}", format.footer());
+    }
+    if let Some(file) = &mut files.output {
+        if write!(file, "{}", cli.output_format.footer()).is_err() {
+            report_write_error(cli, renderer);
+        }
+    }
+}
+
+/// Prints the results of checking a single password, `streamed` is set if it is one of several
+/// reported in the same stream, with --batch or --repeat. `results` is only called if the tests
+/// should be run at all. Returns the code the program should exit with
+fn check_password<'c>(
+    cli: &Cli,
+    checker: &'c Checker,
+    renderer: &Renderer,
+    password: &str,
+    streamed: bool,
+    results: impl FnOnce() -> Vec<TestResult<'c>>,
+    files: &mut Files,
+) -> u8 {
+    let policy = &checker.policy;
+    // What is printed in place of the password
+    let shown = checker.shown(password);
+    if password.trim().is_empty() {
+        if cli.check {
+            return EXIT_REJECTED;
+        }
+        let reason = if password.is_empty() {
+            "The password is empty"
+        } else {
+            "The password only contains whitespace"
+        };
+        // Machine readable reports get every test skipped with the reason instead
+        if cli.format.is_none() {
+            println!(
+                "{} {reason}, not running any tests.",
+                renderer.status(Outcome::Failure, &cli.failure_label)
+            );
+        }
+        let results = checker.skipped(reason);
+        write_reports(cli, checker, renderer, password, &results, streamed, files);
+        // Like any other password that isn't accepted
+        return exit_code(cli, policy, false, 0);
+    }
+
+    if cli.sha1_hash.is_none() && checker.is_allowlisted(password) {
+        if cli.check {
+            return 0;
+        }
+        if cli.format.is_none() {
+            println!(
+                "Password {} is {}, skipped all tests",
+                renderer.paint(Stdout, &shown, renderer.password),
+                renderer.paint(Stdout, "allowlisted", renderer.success)
+            );
+        }
+        let results = checker.skipped("The password is allowlisted");
+        write_reports(cli, checker, renderer, password, &results, streamed, files);
+        return 0;
+    }
+
+    if cli.compare {
+        let [_, _] = checker.wordlists.as_slice() else {
+            eprintln!(
+                "{}",
+                renderer.paint(
+                    Stderr,
+                    "--compare requires exactly two wordlists, given with --wordlist. Aborting.",
+                    renderer.failure
                 )
-            },
-            Ignore::MinimumChars,
-        ),
-        Test::new(
-            "numbers".to_string(),
-            |_cli: &Cli, pass: &str| {
-                // pass.
-                let outcome = pass.chars().any(|c| c.is_ascii_digit());
-                (
-                    Some(outcome),
-                    Cow::Borrowed(if outcome {
-                        ""
-                    } else {
-                        "No numeric chacacters in password"
-                    }),
+            );
+            return 1;
+        };
+        let rows: Vec<_> = policy
+            .wordlists
+            .iter()
+            .zip(&checker.wordlists)
+            .map(|(wordlist_path, wordlist)| {
+                let best = best_match(
+                    password,
+                    &wordlist.filtered_entries(policy),
+                    policy.tie_break,
+                    policy.algorithm,
+                );
+                (wordlist_path.display().to_string(), best)
+            })
+            .collect();
+        let threshold = policy.similarity_threshold();
+        let longest_name = rows
+            .iter()
+            .fold(0, |acc, (name, _)| name.chars().count().max(acc))
+            + 4;
+        println!(
+            "Password:{}{}",
+            " ".repeat(longest_name.saturating_sub(8)),
+            renderer.paint(Stdout, &shown, renderer.password)
+        );
+        for (name, best) in rows {
+            print!("{name}:{}", " ".repeat(longest_name - name.chars().count()));
+            let Some((checkpass, similarity)) = best else {
+                println!(
+                    "{} wordlist is empty",
+                    renderer.paint(Stdout, "missed", renderer.success)
+                );
+                continue;
+            };
+            let (status, style) = if similarity >= threshold {
+                ("caught", renderer.failure)
+            } else {
+                ("missed", renderer.success)
+            };
+            println!(
+                "{} best match is {} with similarity {}",
+                renderer.paint(Stdout, status, style),
+                checker.shown(&checkpass),
+                percentage(similarity)
+            );
+        }
+        return 0;
+    }
+
+    if let Some(cutoff) = cli.dump_matches {
+        let entries: Vec<&str> = checker
+            .active_wordlists()
+            .iter()
+            .flat_map(|wordlist| wordlist.filtered_entries(policy))
+            .collect();
+        let matches = matches_above(
+            password,
+            &entries,
+            f64::from(cutoff) / 100.0,
+            policy.algorithm,
+        );
+        let rows: Vec<(String, f64)> = matches
+            .into_iter()
+            .map(|(entry, similarity)| (checker.shown(entry).into_owned(), similarity))
+            .collect();
+        let longest_name = rows.iter().fold("Password".len(), |acc, (entry, _)| {
+            display_width(entry).max(acc)
+        }) + 4;
+        println!(
+            "Password:{}{}",
+            " ".repeat(longest_name.saturating_sub(8)),
+            renderer.paint(Stdout, &shown, renderer.password)
+        );
+        if rows.is_empty() {
+            println!("No wordlist entry is more similar than {cutoff}%");
+        }
+        for (entry, similarity) in rows {
+            println!(
+                "{}:{}{}",
+                entry,
+                " ".repeat(longest_name - display_width(&entry)),
+                renderer.paint(Stdout, percentage(similarity), renderer.figure)
+            );
+        }
+        return 0;
+    }
+
+    let mut results = results();
+    if cli.by_severity {
+        results.sort_by_key(|result| std::cmp::Reverse(result.severity));
+    }
+    let longest_name = results.iter().fold(0, |acc, TestResult { name, .. }| {
+        display_width(name).max(acc)
+    }) + 4;
+    // Machine readable reports on STDOUT replace the human readable output
+    let human = cli.format.is_none() && !cli.check;
+    if human {
+        println!(
+            "Password:{}{}",
+            " ".repeat(longest_name.saturating_sub(8)),
+            renderer.paint(Stdout, &shown, renderer.password)
+        );
+    }
+    let tally = Tally::of(results.iter().map(|result| result.outcome));
+    for result in results.iter().filter(|_| human) {
+        let TestResult {
+            name: expl,
+            ignore,
+            outcome,
+            info,
+            severity,
+            ..
+        } = result;
+        let difference = longest_name - display_width(expl);
+        print!("{}:{}", renderer.name(Stdout, expl), " ".repeat(difference));
+        let severity = if cli.by_severity {
+            format!(
+                " ({})",
+                renderer.paint(
+                    Stdout,
+                    severity.name(),
+                    renderer.severities[*severity as usize]
                 )
-            },
-            Ignore::Numbers,
-        ),
-        Test::new(
-            "quirky characters".to_string(),
-            |_cli: &Cli, pass: &str| {
-                // pass.
-                let outcome = pass.chars().any(|c| c.is_ascii_punctuation());
-                (
-                    Some(outcome),
-                    Cow::Borrowed(if outcome {
-                        ""
-                    } else {
-                        "No special chacacters in password"
-                    }),
+            )
+        } else {
+            String::new()
+        };
+        // Only the verdict of the collision tests, unless the rank was asked for
+        if cli.no_match_details
+            && !policy.show_match_rank
+            && MATCH_DETAIL_TESTS.contains(ignore)
+            && *outcome != Outcome::Ignored
+        {
+            let label = match outcome {
+                Outcome::Success => &cli.success_label,
+                Outcome::Warning => &cli.warning_label,
+                Outcome::Failure => &cli.failure_label,
+                Outcome::Ignored => &cli.ignored_label,
+                Outcome::Advisory => &cli.advisory_label,
+            };
+            println!("{}{severity}", renderer.status(*outcome, label));
+            continue;
+        }
+        match outcome {
+            Outcome::Success => {
+                println!(
+                    "{}{severity}",
+                    renderer.status(Outcome::Success, &cli.success_label)
+                );
+                if !info.is_empty() {
+                    println!("Additional info: {}", info)
+                }
+            }
+            Outcome::Warning => {
+                println!(
+                    "{}{severity}",
+                    renderer.status(Outcome::Warning, &cli.warning_label)
+                );
+                println!(
+                    "Additional info: {}",
+                    renderer.paint(Stdout, info, renderer.warning)
                 )
-            },
-            Ignore::SpecialChars,
-        ),
-        Test::new(
-            "collisions in wordlist".to_string(),
-            |cli: &Cli, pass: &str| {
-                let mut info = String::new();
-                // Read wordlist from file if provided, default to internal otherwise
-                let wordlist = if let Some(wordlist_path) = cli.wordlist.as_deref() {
-                    let Ok(wordlist) = read_to_string(&wordlist_path) else {
-            // If the given file doesn't exist
-            info = format!("Failed to read file '{}'. Aborting.", wordlist_path.display().if_supports_color(Stderr, |x|x.red()));
-            return (Some(false), Cow::Owned(info))
+            }
+            Outcome::Failure => {
+                println!(
+                    "{}{severity}",
+                    renderer.status(Outcome::Failure, &cli.failure_label)
+                );
+                println!(
+                    "Additional info: {}",
+                    renderer.paint(Stdout, info, renderer.failure)
+                )
+            }
+            Outcome::Ignored => {
+                println!(
+                    "{}{severity}",
+                    renderer.status(Outcome::Ignored, &cli.ignored_label)
+                );
+                println!(
+                    "Additional info: {}",
+                    renderer.paint(Stdout, info, renderer.ignored)
+                )
+            }
+            Outcome::Advisory => {
+                println!(
+                    "{}{severity}",
+                    renderer.status(Outcome::Advisory, &cli.advisory_label)
+                );
+                println!(
+                    "Additional info: {}",
+                    renderer.paint(Stdout, info, renderer.advisory_failure)
+                )
+            }
+        }
+    }
+    if human {
+        let warnings = if tally.warnings > 0 {
+            format!(
+                "{} with warnings, ",
+                renderer.paint(Stdout, tally.warnings, renderer.warning)
+            )
+        } else {
+            String::new()
         };
-                    WordlistType::External(wordlist)
-                } else {
-                    info = format!(
-            "{}",
-            "No wordlist provided, defaulting to internal wordlist(10k most common passwords)."
-                .if_supports_color(Stderr, |x| x.blue())
+        let advisory = if tally.advisory > 0 {
+            format!(
+                "{} advisory failed, ",
+                renderer.paint(Stdout, tally.advisory, renderer.advisory_failure)
+            )
+        } else {
+            String::new()
+        };
+        println!(
+            "Passed {} out of {} tests ({}%), {warnings}{advisory}{} ignored",
+            renderer.paint(Stdout, tally.passed, renderer.highlight),
+            renderer.paint(Stdout, tally.ran(), renderer.highlight),
+            renderer.paint(Stdout, tally.percentage(), renderer.figure),
+            renderer.paint(Stdout, tally.ignored, renderer.ignored)
         );
-                    WORDLIST.to_owned()
-                };
-                // At this point we have the wordlist set correctly and  ensured that the test
-                // should not be ignored
-                let outcome = match wordlist {
-                    WordlistType::Internal(lines) => {
-                        let outcome = find_best_similarity(pass, &lines);
-                        if let Some((checkpass, similarity)) = &outcome {
-                            info = format!(
-                                "Best match in wordlist is {} with similarity {}%",
-                                checkpass,
-                                similarity * 100.0
-                            )
-                        }
-                        outcome.map(|x| x.to_owned())
-                    }
-                    WordlistType::External(string) => {
-                        let outcome =
-                            find_best_similarity(pass, &string.lines().collect::<Vec<_>>());
-                        if let Some((checkpass, similarity)) = &outcome {
-                            info = format!(
-                                "Best match in wordlist is {} with similarity {}%",
-                                checkpass,
-                                similarity * 100.0
-                            )
-                        }
-                        outcome
-                    }
-                };
-                (
-                    Some(
-                        outcome.is_some()
-                            && outcome.unwrap().1
-                                < (cli.similarity.unwrap_or(97).min(99) as f64 / 100.0),
-                    ),
-                    Cow::Owned(info),
+        if cli.sha1_hash.is_some() {
+            let skipped = results
+                .iter()
+                .filter(|result| result.info == NEEDS_PLAINTEXT)
+                .count();
+            println!(
+                "{}",
+                renderer.paint(
+                    Stdout,
+                    format!("Only the SHA-1 hash was given, {skipped} tests need the plaintext password"),
+                    renderer.advisory
                 )
-            },
-            Ignore::WordlistCollisions,
-        ),
-    ];
-    let longest_name = tests.iter().fold(0, |acc, Test { name, .. }| {
-        (name.chars().count() - name.chars().filter(|x| x == &'\u{1b}').count() * 5).max(acc)
-    }) + 4;
-    println!(
-        "Password:{}{}",
-        " ".repeat(longest_name.checked_sub(8).unwrap_or(0)),
-        password.bold().blue()
-    );
-    let mut enabled_count = 0u32;
-    let successes = tests
-        .iter()
-        .filter(
-            |Test {
-                 name: expl,
-                 test,
-                 ignore,
-             }| {
-                let difference = longest_name
-                    - (expl.chars().count() - expl.chars().filter(|x| x == &'\u{1b}').count() * 5);
-                print!("{expl}:{}", " ".repeat(difference));
-                // Only execute the logic if enable_wordlist is true or was not provided
-                let (outcome, info) =
-                    if cli.ignore.as_deref().map(|x| x.contains(&ignore)) == Some(true) {
-                        (None, Cow::Owned(format!("disabled with -i {ignore:?}")))
-                    } else {
-                        test(&cli, &password)
-                    };
-                match outcome {
-                    Some(true) => {
-                        println!(
-                            "{}",
-                            "success".if_supports_color(Stdout, |x| x.style(success_style))
-                        );
-                        if info != "" {
-                            println!("Additional info: {}", info)
-                        }
-                    }
-                    Some(false) => {
-                        println!(
-                            "{}",
-                            "failure".if_supports_color(Stdout, |x| x.style(failure_style))
-                        );
-                        println!(
-                            "Additional info: {}",
-                            info.if_supports_color(Stdout, |x| x.style(failure_style))
-                        )
-                    }
-                    None => {
-                        println!(
-                            "{}",
-                            "ignored".if_supports_color(Stdout, |x| x.style(ignored_style))
-                        );
-                        println!(
-                            "Additional info: {}",
-                            info.if_supports_color(Stdout, |x| x.style(ignored_style))
-                        )
-                    }
+            );
+        } else {
+            let (log10_guesses, tier) = checker.strength(password);
+            let style = match tier {
+                Tier::VeryWeak | Tier::Weak => renderer.failure,
+                Tier::Fair => renderer.warning,
+                Tier::Strong | Tier::VeryStrong => renderer.success,
+            };
+            let required = match policy.min_tier {
+                Some(min_tier) if tier >= min_tier => {
+                    format!(", at least {} required", min_tier.name())
                 }
-                if outcome.is_some() {
-                    enabled_count += 1
+                Some(min_tier) => format!(
+                    ", {}",
+                    renderer.paint(
+                        Stdout,
+                        format!("at least {} required", min_tier.name()),
+                        renderer.failure
+                    )
+                ),
+                None => String::new(),
+            };
+            println!(
+                "Strength: {} (10^{log10_guesses:.1} guesses){required}",
+                renderer.paint(Stdout, tier.name(), style)
+            );
+            if cli.crack_times {
+                let times = policy.crack_times(log10_guesses);
+                for (scenario, time) in [("online", times.online), ("offline", times.offline)] {
+                    println!(
+                        "Crack time {scenario}:{}{} at {} guesses per second",
+                        " ".repeat(8 - scenario.len()),
+                        renderer.paint(Stdout, time.describe(), renderer.figure),
+                        time.guesses_per_second
+                    );
                 }
-                outcome.unwrap_or(false)
-            },
-        )
-        .count();
-    println!(
-        "Passed {} out of {} tests ({}%), {} ignored",
-        successes.if_supports_color(Stdout, |x| x.blue()),
-        enabled_count.if_supports_color(Stdout, |x| x.blue()),
-        (successes as f32 / enabled_count as f32 * 100.0).if_supports_color(Stdout, |x| x.yellow()),
-        (tests.len() - enabled_count as usize)
-            .if_supports_color(Stdout, |x| x.style(ignored_style))
-    );
+            }
+        }
+        let skipped = results
+            .iter()
+            .filter(|result| result.info == BUDGET_EXHAUSTED)
+            .count();
+        if skipped > 0 {
+            println!(
+                "{}",
+                renderer.paint(
+                    Stdout,
+                    format!("Skipped {skipped} tests, the --max-time-ms budget was used up"),
+                    renderer.advisory
+                )
+            );
+        }
+    }
+    if human {
+        if cli.checklist {
+            let steps = checker.checklist(password, &results);
+            if !steps.is_empty() {
+                println!("Checklist:");
+            }
+            let checkbox = if renderer.ascii { "[ ]" } else { "☐" };
+            for step in steps {
+                println!(
+                    "{checkbox} {}",
+                    renderer.paint(Stdout, &step, renderer.advisory)
+                );
+            }
+        } else if let Some(suggestion) = suggestion(checker, password, &results) {
+            println!(
+                "Suggestion: {}",
+                renderer.paint(Stdout, &suggestion, renderer.advisory)
+            );
+        }
+    }
+    write_reports(cli, checker, renderer, password, &results, streamed, files);
+    // A hash has no tier, so it is only held to the tests that ran
+    let accepted = if cli.sha1_hash.is_some() {
+        policy.accepts(&results)
+    } else {
+        checker.accepts(password, &results)
+    };
+    if let Some(file) = &mut files.audit {
+        let password = (!cli.report_no_password).then(|| mask(password));
+        let record = Audit::new(&results, accepted, password, cli.report_results);
+        // Flushed after every line so the log is complete even if the process is killed
+        if writeln!(file, "{}", record.render())
+            .and_then(|_| file.flush())
+            .is_err()
+        {
+            eprintln!(
+                "{}",
+                renderer.paint(
+                    Stderr,
+                    format!(
+                        "Failed to append to report file '{}'.",
+                        cli.report_file
+                            .as_deref()
+                            .unwrap_or(Path::new(""))
+                            .display()
+                    ),
+                    renderer.failure
+                )
+            );
+        }
+    }
+    if cli.stats {
+        eprintln!("Time taken by each test:");
+        for TestResult { name, duration, .. } in &results {
+            let difference = longest_name - display_width(name);
+            eprintln!(
+                "{}:{}{}",
+                renderer.name(Stderr, name),
+                " ".repeat(difference),
+                renderer.paint(Stderr, format!("{duration:.2?}"), renderer.figure)
+            );
+        }
+    }
+    exit_code(cli, policy, accepted, tally.warnings)
+}
+
+/// The code to exit with for a password that was `accepted` or not with `warnings` warnings,
+/// asking for confirmation first with --confirm if it wasn't
+fn exit_code(cli: &Cli, policy: &Policy, accepted: bool, warnings: usize) -> u8 {
+    if cli.confirm && !accepted {
+        return if confirmed(cli) { 0 } else { EXIT_REJECTED };
+    }
+    if !cli.check {
+        // The tier gate sets the exit code on its own
+        return if policy.min_tier.is_some() && !accepted {
+            EXIT_REJECTED
+        } else {
+            0
+        };
+    }
+    match (accepted, warnings) {
+        (false, _) => EXIT_REJECTED,
+        (true, 0) => 0,
+        (true, _) if cli.warnings_as_errors => EXIT_REJECTED,
+        (true, _) => EXIT_WARNINGS,
+    }
 }
@@ -0,0 +1,153 @@
+//! A letter bigram model of English, estimating the entropy of a password from how predictable
+//! each letter is after the one before it, so "thereason" scores far lower than "xqjzvkwpf" of
+//! the same length and alphabet.
+//!
+//! The frequencies are the ones Peter Norvig published in "English Letter Frequency Counts:
+//! Mayzner Revisited" (2012), counted over the English Google Books n-grams: the share of each
+//! letter among all letters and the shares of the 50 most common bigrams among all bigrams, as
+//! percentages rounded to two decimals. The less common bigrams share what is left of the
+//! bigrams in proportion to the frequencies of their two letters, which keeps the model to a few
+//! hundred bytes.
+use std::sync::LazyLock;
+
+/// The share of every letter from `a` to `z` among all letters, as a percentage
+// The 6.28 of "r" isn't meant as tau
+#[allow(clippy::approx_constant)]
+pub const LETTERS: [f64; 26] = [
+    8.04, 1.48, 3.34, 3.82, 12.49, 2.40, 1.87, 5.05, 7.57, 0.16, 0.54, 4.07, 2.51, 7.23, 7.64,
+    2.14, 0.12, 6.28, 6.51, 9.28, 2.73, 1.05, 1.68, 0.23, 1.66, 0.09,
+];
+
+/// The 50 most common bigrams along with their share among all bigrams, as a percentage
+pub const BIGRAMS: &[(&str, f64)] = &[
+    ("th", 3.56),
+    ("he", 3.07),
+    ("in", 2.43),
+    ("er", 2.05),
+    ("an", 1.99),
+    ("re", 1.85),
+    ("on", 1.76),
+    ("at", 1.49),
+    ("en", 1.45),
+    ("nd", 1.35),
+    ("ti", 1.34),
+    ("es", 1.34),
+    ("or", 1.28),
+    ("te", 1.20),
+    ("of", 1.17),
+    ("ed", 1.17),
+    ("is", 1.13),
+    ("it", 1.12),
+    ("al", 1.09),
+    ("ar", 1.07),
+    ("st", 1.05),
+    ("to", 1.04),
+    ("nt", 1.04),
+    ("ng", 0.95),
+    ("se", 0.93),
+    ("ha", 0.93),
+    ("as", 0.87),
+    ("ou", 0.87),
+    ("io", 0.83),
+    ("le", 0.83),
+    ("ve", 0.83),
+    ("co", 0.79),
+    ("me", 0.79),
+    ("de", 0.76),
+    ("hi", 0.76),
+    ("ri", 0.73),
+    ("ro", 0.73),
+    ("ic", 0.70),
+    ("ne", 0.69),
+    ("ea", 0.69),
+    ("ra", 0.69),
+    ("ce", 0.65),
+    ("li", 0.62),
+    ("ch", 0.60),
+    ("ll", 0.58),
+    ("be", 0.58),
+    ("ma", 0.57),
+    ("si", 0.55),
+    ("om", 0.55),
+    ("ur", 0.54),
+];
+
+/// The bits of a digit, any of the 10 being as likely
+const DIGIT_BITS: f64 = std::f64::consts::LOG2_10;
+/// The bits of one of the 33 printable ASCII symbols, space included
+const SYMBOL_BITS: f64 = 5.044394;
+/// The bits of a character outside of ASCII, as one of the 2^7 characters an attacker would try
+const OTHER_BITS: f64 = 7.0;
+
+/// The bits of every letter after every other letter, `BITS[a][b]` being the bits of `b` after
+/// `a`, along with the bits of every letter on its own in `BITS[26]`
+static BITS: LazyLock<[[f64; 26]; 27]> = LazyLock::new(|| {
+    let letter = |ch: u8| usize::from(ch - b'a');
+    let mut pairs = [[None; 26]; 26];
+    for (bigram, share) in BIGRAMS {
+        let [a, b] = bigram.as_bytes() else {
+            unreachable!("Bigrams are two letters")
+        };
+        pairs[letter(*a)][letter(*b)] = Some(share / 100.0);
+    }
+    let singles = LETTERS.map(|share| share / 100.0);
+    // What the listed bigrams leave for the others, spread by the frequencies of their letters
+    let listed: f64 = BIGRAMS.iter().map(|(_, share)| share / 100.0).sum();
+    let unlisted: f64 = (0..26)
+        .flat_map(|a| (0..26).map(move |b| (a, b)))
+        .filter(|&(a, b)| pairs[a][b].is_none())
+        .map(|(a, b)| singles[a] * singles[b])
+        .sum();
+    let mut bits = [[0.0; 26]; 27];
+    for (a, row) in pairs.iter().enumerate() {
+        let shares: Vec<f64> = (0..26)
+            .map(|b| row[b].unwrap_or((1.0 - listed) * singles[a] * singles[b] / unlisted))
+            .collect();
+        let total: f64 = shares.iter().sum();
+        for b in 0..26 {
+            bits[a][b] = -(shares[b] / total).log2();
+        }
+    }
+    let total: f64 = singles.iter().sum();
+    bits[26] = singles.map(|share| -(share / total).log2());
+    bits
+});
+
+/// The estimated entropy of `password` in bits. Letters are scored by the model, without regard
+/// to case but with a bit for every uppercase one, the first letter and every letter after
+/// another character on its own. Digits, symbols and other characters are scored as if picked at
+/// random from their kind
+///
+/// ```
+/// use passchecker::ngram::bits;
+///
+/// assert!(bits("thereason") < 30.0);
+/// assert!(bits("xqjzvkwpf") > 60.0);
+/// assert!(bits("Thereason") > bits("thereason"));
+/// assert_eq!(bits(""), 0.0);
+/// ```
+pub fn bits(password: &str) -> f64 {
+    let mut previous = None;
+    let mut total = 0.0;
+    for ch in password.chars() {
+        let lowercase = ch.to_ascii_lowercase();
+        previous = if lowercase.is_ascii_lowercase() {
+            let letter = usize::from(lowercase as u8 - b'a');
+            total += BITS[previous.unwrap_or(26)][letter];
+            if ch.is_ascii_uppercase() {
+                total += 1.0
+            }
+            Some(letter)
+        } else {
+            total += if ch.is_ascii_digit() {
+                DIGIT_BITS
+            } else if ch.is_ascii_graphic() || ch == ' ' {
+                SYMBOL_BITS
+            } else {
+                OTHER_BITS
+            };
+            None
+        };
+    }
+    total
+}
@@ -0,0 +1,196 @@
+//! A minimal pattern scorer in the spirit of zxcvbn, splitting a password into the chunks an
+//! attacker would guess separately and estimating how many guesses the whole password takes
+use std::collections::HashMap;
+use std::fmt;
+
+/// What a [`Chunk`] of the password was recognised as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// A wordlist entry, `rank` being its position in the wordlist starting from 1
+    Dictionary { rank: usize },
+    /// Consecutive characters going up or down, "abcd" or "9876"
+    Sequence,
+    /// The same character over and over, "aaaa"
+    Repeat,
+    /// A year between 1900 and 2099
+    Year,
+    /// A day, month and year written as 6 or 8 digits
+    Date,
+    /// Characters that fit no pattern and have to be guessed one by one
+    Bruteforce,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::Dictionary { rank } => write!(f, "wordlist entry ranked {rank}"),
+            Kind::Sequence => write!(f, "sequence"),
+            Kind::Repeat => write!(f, "repeat"),
+            Kind::Year => write!(f, "year"),
+            Kind::Date => write!(f, "date"),
+            Kind::Bruteforce => write!(f, "bruteforce"),
+        }
+    }
+}
+
+/// A part of the password along with the estimated guesses it takes, as a power of 10
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub text: String,
+    pub kind: Kind,
+    pub log10_guesses: f64,
+}
+
+/// Splits `password` into the chunks that take the fewest guesses overall, `ranks` maps lowercase
+/// wordlist entries to their rank. The estimated guesses of the whole password, as a power of 10,
+/// are the sum of the `log10_guesses` of the chunks
+pub fn decompose(password: &str, ranks: &HashMap<String, usize>) -> Vec<Chunk> {
+    let chars: Vec<char> = password.chars().collect();
+    let lowercase: Vec<char> = chars
+        .iter()
+        .map(|ch| ch.to_lowercase().next().unwrap_or(*ch))
+        .collect();
+    let longest_entry = ranks.keys().map(|entry| entry.chars().count()).max();
+    // The cheapest way to guess the first `end` characters, along with where its last chunk
+    // starts and what it is
+    let mut best: Vec<(f64, usize, Kind, f64)> = vec![(0.0, 0, Kind::Bruteforce, 0.0)];
+    for end in 1..=chars.len() {
+        let brute = cardinality(chars[end - 1]).log10();
+        let mut cheapest = (best[end - 1].0 + brute, end - 1, Kind::Bruteforce, brute);
+        for start in 0..end.saturating_sub(2) {
+            let window = &chars[start..end];
+            let lowercase_window = &lowercase[start..end];
+            let candidates = [
+                longest_entry
+                    .filter(|longest| window.len() <= *longest)
+                    .and_then(|_| dictionary(window, lowercase_window, ranks)),
+                sequence(window),
+                repeat(window),
+                date(window),
+            ];
+            for (kind, log10_guesses) in candidates.into_iter().flatten() {
+                let total = best[start].0 + log10_guesses;
+                if total < cheapest.0 {
+                    cheapest = (total, start, kind, log10_guesses);
+                }
+            }
+        }
+        best.push(cheapest);
+    }
+    // Walk back from the end to recover the chunks, merging neighbouring bruteforced characters
+    let mut chunks: Vec<Chunk> = Vec::new();
+    let mut end = chars.len();
+    while end > 0 {
+        let (_, start, kind, log10_guesses) = best[end];
+        let text: String = chars[start..end].iter().collect();
+        match chunks.last_mut() {
+            Some(last) if kind == Kind::Bruteforce && last.kind == Kind::Bruteforce => {
+                last.text.insert_str(0, &text);
+                last.log10_guesses += log10_guesses;
+            }
+            _ => chunks.push(Chunk {
+                text,
+                kind,
+                log10_guesses,
+            }),
+        }
+        end = start;
+    }
+    chunks.reverse();
+    chunks
+}
+
+/// How many characters an attacker bruteforcing `ch` has to try
+fn cardinality(ch: char) -> f64 {
+    if ch.is_ascii_digit() {
+        10.0
+    } else if ch.is_ascii_alphabetic() {
+        26.0
+    } else if ch.is_ascii() {
+        33.0
+    } else {
+        100.0
+    }
+}
+
+fn dictionary(
+    window: &[char],
+    lowercase: &[char],
+    ranks: &HashMap<String, usize>,
+) -> Option<(Kind, f64)> {
+    let entry: String = lowercase.iter().collect();
+    let rank = *ranks.get(&entry)?;
+    // Every uppercase letter doubles the variations to try, a capitalised first letter is the
+    // first one an attacker tries
+    let uppercase = window.iter().filter(|ch| ch.is_uppercase()).count();
+    let variations = match uppercase {
+        0 => 1.0,
+        1 if window[0].is_uppercase() => 2.0,
+        uppercase => 2f64.powi(uppercase as i32),
+    };
+    Some((
+        Kind::Dictionary { rank },
+        (rank as f64 * variations).log10(),
+    ))
+}
+
+fn sequence(window: &[char]) -> Option<(Kind, f64)> {
+    let delta = window[1] as i64 - window[0] as i64;
+    if delta.abs() != 1 {
+        return None;
+    }
+    let consecutive = window
+        .windows(2)
+        .all(|pair| pair[1] as i64 - pair[0] as i64 == delta);
+    if !consecutive {
+        return None;
+    }
+    // Sequences starting at either end of the alphabet or digits are tried first
+    let base = match window[0] {
+        'a' | 'A' | 'z' | 'Z' | '0' | '1' | '9' => 4.0,
+        ch if ch.is_ascii_digit() => 10.0,
+        _ => 26.0,
+    };
+    let direction = if delta < 0 { 2.0 } else { 1.0 };
+    Some((
+        Kind::Sequence,
+        (base * window.len() as f64 * direction).log10(),
+    ))
+}
+
+fn repeat(window: &[char]) -> Option<(Kind, f64)> {
+    if window.iter().any(|ch| *ch != window[0]) {
+        return None;
+    }
+    Some((
+        Kind::Repeat,
+        (cardinality(window[0]) * window.len() as f64).log10(),
+    ))
+}
+
+fn date(window: &[char]) -> Option<(Kind, f64)> {
+    let digits: Option<Vec<u32>> = window.iter().map(|ch| ch.to_digit(10)).collect();
+    let digits = digits?;
+    let number = |digits: &[u32]| digits.iter().fold(0, |acc, digit| acc * 10 + digit);
+    let valid = |day: u32, month: u32| (1..=31).contains(&day) && (1..=12).contains(&month);
+    let year = |year: u32| (1900..=2099).contains(&year);
+    match digits.as_slice() {
+        digits @ [_, _, _, _] if year(number(digits)) => Some((Kind::Year, 200f64.log10())),
+        // ddmmyy and mmddyy
+        [a, b, c, d, _, _] => {
+            let (first, second) = (number(&[*a, *b]), number(&[*c, *d]));
+            (valid(first, second) || valid(second, first))
+                .then_some((Kind::Date, (365.0 * 100f64).log10()))
+        }
+        // ddmmyyyy, mmddyyyy and yyyymmdd
+        [a, b, c, d, e, f, g, h] => {
+            let leading_year =
+                year(number(&[*a, *b, *c, *d])) && valid(number(&[*g, *h]), number(&[*e, *f]));
+            let trailing_year = year(number(&[*e, *f, *g, *h]))
+                && (valid(number(&[*a, *b]), number(&[*c, *d]))
+                    || valid(number(&[*c, *d]), number(&[*a, *b])));
+            (leading_year || trailing_year).then_some((Kind::Date, (365.0 * 200f64).log10()))
+        }
+        _ => None,
+    }
+}
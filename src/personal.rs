@@ -0,0 +1,68 @@
+//! Patterns of personal numbers people tend to reuse as passwords
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// A kind of personal number, recognised when the whole password matches `regex` and passes
+/// `validate`
+pub struct Pattern {
+    pub name: &'static str,
+    pub regex: &'static str,
+    /// Further checks the digits of the password have to pass, such as a checksum
+    pub validate: Option<fn(&[u32]) -> bool>,
+}
+
+/// Every pattern, the first one that matches is reported. New patterns only have to be added here
+pub const PATTERNS: &[Pattern] = &[
+    // 13 to 19 digits, optionally grouped with spaces or dashes, with a valid Luhn checksum
+    Pattern {
+        name: "credit card number",
+        regex: r"^[0-9](?:[ -]?[0-9]){12,18}$",
+        validate: Some(luhn),
+    },
+    // 123-45-6789 or 123456789
+    Pattern {
+        name: "social security number",
+        regex: r"^[0-9]{3}-?[0-9]{2}-?[0-9]{4}$",
+        validate: None,
+    },
+    // (555) 123-4567, 555.123.4567 or +1 555 123 4567
+    Pattern {
+        name: "phone number",
+        regex: r"^(?:\+?[0-9]{1,3}[ .-]?)?(?:\([0-9]{3}\)|[0-9]{3})[ .-]?[0-9]{3}[ .-]?[0-9]{4}$",
+        validate: None,
+    },
+];
+
+static COMPILED: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    PATTERNS
+        .iter()
+        .map(|pattern| Regex::new(pattern.regex).expect("Personal data patterns are valid"))
+        .collect()
+});
+
+/// The name of the first of [`PATTERNS`] `password` matches
+pub fn detect(password: &str) -> Option<&'static str> {
+    let digits: Vec<u32> = password.chars().filter_map(|ch| ch.to_digit(10)).collect();
+    PATTERNS
+        .iter()
+        .zip(COMPILED.iter())
+        .find(|(pattern, regex)| {
+            regex.is_match(password) && pattern.validate.is_none_or(|validate| validate(&digits))
+        })
+        .map(|(pattern, _)| pattern.name)
+}
+
+/// Whether the last digit is the Luhn checksum of the others, as on payment cards
+fn luhn(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(index, &digit)| match index % 2 {
+            0 => digit,
+            _ if digit * 2 > 9 => digit * 2 - 9,
+            _ => digit * 2,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
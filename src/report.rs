@@ -0,0 +1,357 @@
+//! Machine readable reports of the test results
+use crate::{CrackTimes, Ignore, Outcome, Severity, Tally, TestResult, Tier};
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const CSV_HEADER: &str = "password,test,name,outcome,info";
+pub const JUNIT_HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n";
+pub const JUNIT_FOOTER: &str = "</testsuites>\n";
+/// The version of the structure of JSON reports, following semver: the minor version is bumped
+/// when fields are added, the major one when fields are removed, renamed or change meaning
+pub const SCHEMA_VERSION: &str = "1.4.0";
+
+/// The machine readable formats a [`Report`] can be rendered in
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Csv,
+    /// JUnit XML, each password being a test suite and each test a test case
+    Junit,
+}
+
+impl Format {
+    /// What starts a stream of reports rendered with [`Report::render_line`]: the CSV header row
+    /// if `csv` has one, or the opening of the root element of JUnit XML
+    pub fn header(self, csv: Csv) -> String {
+        match self {
+            Format::Csv if csv.header => {
+                CSV_HEADER.replace(',', &csv.delimiter.char().to_string()) + "\n"
+            }
+            Format::Junit => JUNIT_HEADER.to_string(),
+            Format::Json | Format::Csv => String::new(),
+        }
+    }
+
+    /// What ends a stream of reports rendered with [`Report::render_line`], closing the root
+    /// element of JUnit XML
+    pub fn footer(self) -> &'static str {
+        match self {
+            Format::Junit => JUNIT_FOOTER,
+            Format::Json | Format::Csv => "",
+        }
+    }
+}
+
+/// What separates the fields of CSV reports
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Delimiter {
+    #[default]
+    Comma,
+    /// Tab-separated values
+    Tab,
+    /// The usual separator in regions where the comma is the decimal separator
+    Semicolon,
+}
+
+impl Delimiter {
+    pub fn char(self) -> char {
+        match self {
+            Delimiter::Comma => ',',
+            Delimiter::Tab => '\t',
+            Delimiter::Semicolon => ';',
+        }
+    }
+}
+
+/// How CSV reports are written
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Csv {
+    pub delimiter: Delimiter,
+    /// Whether the report starts with the [`CSV_HEADER`] row
+    pub header: bool,
+}
+
+impl Default for Csv {
+    fn default() -> Self {
+        Self {
+            delimiter: Delimiter::default(),
+            header: true,
+        }
+    }
+}
+
+/// A single test in a [`Report`], with all color escape sequences removed
+#[derive(Serialize, Debug, Clone)]
+pub struct Entry {
+    pub name: String,
+    pub test: Ignore,
+    pub outcome: Outcome,
+    pub info: String,
+    pub severity: Severity,
+}
+
+/// The results of checking a single password
+#[derive(Serialize, Debug, Clone)]
+pub struct Report<'a> {
+    /// Always [`SCHEMA_VERSION`]
+    pub schema_version: &'static str,
+    pub password: &'a str,
+    pub results: Vec<Entry>,
+    /// How many tests passed, including the ones that warned
+    pub passed: usize,
+    pub warnings: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    /// How many advisory tests failed, they aren't counted in `failed`
+    pub advisory: usize,
+    /// The strength tier of the password
+    pub tier: Tier,
+    /// How long guessing the password takes online and offline, with the guess rates assumed
+    pub crack_times: CrackTimes,
+}
+
+impl<'a> Report<'a> {
+    pub fn new(
+        password: &'a str,
+        results: &[TestResult],
+        tier: Tier,
+        crack_times: CrackTimes,
+    ) -> Self {
+        let results: Vec<Entry> = results
+            .iter()
+            .map(|result| Entry {
+                name: result.name.clone(),
+                test: result.ignore.clone(),
+                outcome: result.outcome,
+                info: result.info.to_string(),
+                severity: result.severity,
+            })
+            .collect();
+        let tally = Tally::of(results.iter().map(|x| x.outcome));
+        Self {
+            schema_version: SCHEMA_VERSION,
+            password,
+            passed: tally.passed,
+            warnings: tally.warnings,
+            failed: tally.failed,
+            ignored: tally.ignored,
+            advisory: tally.advisory,
+            tier,
+            crack_times,
+            results,
+        }
+    }
+
+    /// Renders the report in `format`, CSV being written as `csv` says
+    pub fn render(&self, format: Format, csv: Csv) -> String {
+        match format {
+            Format::Json => {
+                serde_json::to_string_pretty(self).expect("Failed to serialize the report")
+            }
+            Format::Csv => format.header(csv) + &self.csv_rows(csv.delimiter),
+            Format::Junit => format!("{JUNIT_HEADER}{}{JUNIT_FOOTER}", self.junit_suite()),
+        }
+    }
+
+    /// Renders the report as part of a stream of reports: a single line of JSON, making the
+    /// stream JSON Lines, or just the CSV rows or JUnit test suite. The stream is started with
+    /// [`Format::header`] and ended with [`Format::footer`], whatever the reports in between
+    pub fn render_line(&self, format: Format, delimiter: Delimiter) -> String {
+        match format {
+            Format::Json => {
+                let json = serde_json::to_string(self).expect("Failed to serialize the report");
+                json + "\n"
+            }
+            Format::Csv => self.csv_rows(delimiter),
+            Format::Junit => self.junit_suite(),
+        }
+    }
+
+    /// The password as a JUnit `<testsuite>`. Failures are `<failure>`s and ignored tests
+    /// `<skipped>` ones, the infos of the other tests are kept as their `<system-out>`
+    fn junit_suite(&self) -> String {
+        let mut xml = format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\">\n",
+            xml_escape(self.password),
+            self.results.len(),
+            self.failed,
+            self.ignored
+        );
+        for entry in &self.results {
+            xml += &format!(
+                "    <testcase name=\"{}\" classname=\"{}\"",
+                xml_escape(&entry.name),
+                entry.test.name()
+            );
+            let info = xml_escape(&entry.info);
+            match entry.outcome {
+                Outcome::Failure => {
+                    xml += &format!(
+                        ">\n      <failure message=\"{info}\">{info}</failure>\n    </testcase>\n"
+                    )
+                }
+                Outcome::Ignored => {
+                    xml += &format!(">\n      <skipped message=\"{info}\"/>\n    </testcase>\n")
+                }
+                _ if info.is_empty() => xml += "/>\n",
+                outcome => {
+                    xml += &format!(
+                        ">\n      <system-out>{}: {info}</system-out>\n    </testcase>\n",
+                        outcome.as_str()
+                    )
+                }
+            }
+        }
+        xml + "  </testsuite>\n"
+    }
+
+    fn csv_rows(&self, delimiter: Delimiter) -> String {
+        let mut csv = String::new();
+        for entry in &self.results {
+            let fields = [
+                self.password,
+                entry.test.name(),
+                &entry.name,
+                entry.outcome.as_str(),
+                &entry.info,
+            ];
+            let fields: Vec<_> = fields
+                .into_iter()
+                .map(|field| csv_field(field, delimiter))
+                .collect();
+            csv += &fields.join(&delimiter.char().to_string());
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// A test in an [`Audit`] record, without the info
+#[derive(Serialize, Debug, Clone)]
+pub struct AuditEntry {
+    pub test: Ignore,
+    pub outcome: Outcome,
+}
+
+/// A line of the audit log appended to with `--report-file`. It never holds the password in the
+/// clear, nor the infos of the tests, which can contain parts of it
+#[derive(Serialize, Debug, Clone)]
+pub struct Audit {
+    /// When the password was checked, in RFC 3339 format and UTC
+    pub timestamp: String,
+    /// The password, masked with [`mask`](crate::mask)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    pub accepted: bool,
+    /// How many tests passed, including the ones that warned
+    pub passed: usize,
+    pub warnings: usize,
+    pub failed: usize,
+    pub ignored: usize,
+    /// How many advisory tests failed, they aren't counted in `failed`
+    pub advisory: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub results: Option<Vec<AuditEntry>>,
+}
+
+impl Audit {
+    /// An audit record of `results` checked now, `password` being the masked password if it should
+    /// be logged
+    pub fn new(
+        results: &[TestResult],
+        accepted: bool,
+        password: Option<String>,
+        with_results: bool,
+    ) -> Self {
+        let tally = Tally::of(results.iter().map(|x| x.outcome));
+        Self {
+            timestamp: timestamp(SystemTime::now()),
+            password,
+            accepted,
+            passed: tally.passed,
+            warnings: tally.warnings,
+            failed: tally.failed,
+            ignored: tally.ignored,
+            advisory: tally.advisory,
+            results: with_results.then(|| {
+                results
+                    .iter()
+                    .map(|result| AuditEntry {
+                        test: result.ignore.clone(),
+                        outcome: result.outcome,
+                    })
+                    .collect()
+            }),
+        }
+    }
+
+    /// The record as a single line of JSON, for a JSON Lines log
+    pub fn render(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize the audit record")
+    }
+}
+
+/// Formats `time` in RFC 3339 format, in UTC and to the second
+///
+/// ```
+/// # use passchecker::report::timestamp;
+/// # use std::time::{Duration, UNIX_EPOCH};
+/// let time = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// assert_eq!(timestamp(time), "2023-11-14T22:13:20Z");
+/// assert_eq!(timestamp(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+/// ```
+pub fn timestamp(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |x| x.as_secs());
+    let (days, seconds) = (seconds / 86400, seconds % 86400);
+    // Converts days since 1970 to a date in the proleptic Gregorian calendar, counting years from
+    // March so the leap day comes last
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Quotes a CSV field if it contains anything that would break the row
+fn csv_field(field: &str, delimiter: Delimiter) -> String {
+    if field.contains([delimiter.char(), '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// `text` with the characters XML gives a meaning to escaped, and the control characters XML
+/// doesn't allow at all left out
+///
+/// ```
+/// # use passchecker::report::xml_escape;
+/// assert_eq!(xml_escape("<a href=\"x\">&\u{7}</a>"), "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+/// ```
+pub fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            '\'' => escaped += "&apos;",
+            '\t' | '\n' | '\r' => escaped.push(ch),
+            ch if ch.is_control() => {}
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
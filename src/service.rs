@@ -0,0 +1,116 @@
+//! The password rules of well-known services, checked by `--service` to tell whether a password
+//! would be accepted there. A service is only a name and a list of [`Rule`]s, so supporting
+//! another one only takes adding it to [`SERVICES`].
+use crate::charclass::ClassCounts;
+
+/// A single requirement of a service
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// At least this many characters
+    MinLength(u8),
+    /// At most this many characters
+    MaxLength(u8),
+    Lowercase,
+    Uppercase,
+    Digit,
+    Symbol,
+    /// At least this many of lowercase letters, uppercase letters, digits and symbols
+    Classes(u8),
+}
+
+impl Rule {
+    /// Whether `password` meets the rule
+    ///
+    /// ```
+    /// use passchecker::service::Rule;
+    ///
+    /// assert!(Rule::MinLength(8).check("password"));
+    /// assert!(!Rule::Digit.check("password"));
+    /// assert!(Rule::Classes(3).check("Password1"));
+    /// assert!(!Rule::Classes(3).check("password1"));
+    /// ```
+    pub fn check(self, password: &str) -> bool {
+        let counts = ClassCounts::of(password);
+        match self {
+            Rule::MinLength(length) => password.chars().count() >= length.into(),
+            Rule::MaxLength(length) => password.chars().count() <= length.into(),
+            Rule::Lowercase => counts.lower > 0,
+            Rule::Uppercase => counts.upper > 0,
+            Rule::Digit => counts.digit > 0,
+            Rule::Symbol => counts.symbol > 0,
+            Rule::Classes(classes) => {
+                let present = [counts.lower, counts.upper, counts.digit, counts.symbol]
+                    .into_iter()
+                    .filter(|&count| count > 0)
+                    .count();
+                present >= classes.into()
+            }
+        }
+    }
+
+    /// What the rule requires, as it is reported
+    pub fn description(self) -> String {
+        match self {
+            Rule::MinLength(length) => format!("at least {length} characters"),
+            Rule::MaxLength(length) => format!("at most {length} characters"),
+            Rule::Lowercase => "a lowercase letter".to_string(),
+            Rule::Uppercase => "an uppercase letter".to_string(),
+            Rule::Digit => "a number".to_string(),
+            Rule::Symbol => "a symbol".to_string(),
+            Rule::Classes(classes) => {
+                format!("{classes} of lowercase letters, uppercase letters, numbers and symbols")
+            }
+        }
+    }
+}
+
+/// A service and the rules its passwords have to meet
+#[derive(Debug, Clone, Copy)]
+pub struct Service {
+    /// The name `--service` takes
+    pub name: &'static str,
+    /// The name of the service as it is reported
+    pub title: &'static str,
+    pub rules: &'static [Rule],
+}
+
+/// Every known service, the rules are the ones the services document for new passwords
+pub const SERVICES: &[Service] = &[
+    Service {
+        name: "apple",
+        title: "Apple Account",
+        rules: &[
+            Rule::MinLength(8),
+            Rule::Lowercase,
+            Rule::Uppercase,
+            Rule::Digit,
+        ],
+    },
+    Service {
+        name: "aws",
+        title: "AWS IAM",
+        rules: &[Rule::MinLength(8), Rule::MaxLength(128), Rule::Classes(3)],
+    },
+    Service {
+        name: "google",
+        title: "Google Account",
+        rules: &[Rule::MinLength(8), Rule::MaxLength(100)],
+    },
+    Service {
+        name: "microsoft",
+        title: "Microsoft account",
+        rules: &[Rule::MinLength(8), Rule::Classes(2)],
+    },
+];
+
+/// The service named `name`
+///
+/// ```
+/// use passchecker::service;
+///
+/// assert_eq!(service::find("google").unwrap().title, "Google Account");
+/// assert!(service::find("myspace").is_none());
+/// ```
+pub fn find(name: &str) -> Option<&'static Service> {
+    SERVICES.iter().find(|service| service.name == name)
+}
@@ -0,0 +1,115 @@
+//! The metrics the password is compared to wordlist entries, context terms and previous passwords
+//! with
+use similar_string::compare_similarity;
+
+/// How much substituting one character of a pair for the other costs in the weighted edit
+/// distance, any other substitution costing 1. The pairs are the look-alike swaps used to
+/// disguise a known password, so "passw0rd" stays close to "password"
+pub const SUBSTITUTIONS: &[(char, char, f64)] = &[
+    ('a', '4', 0.25),
+    ('a', '@', 0.25),
+    ('b', '8', 0.25),
+    ('c', '(', 0.25),
+    ('e', '3', 0.25),
+    ('g', '9', 0.25),
+    ('i', '1', 0.25),
+    ('i', '!', 0.25),
+    ('i', '|', 0.25),
+    ('l', '1', 0.25),
+    ('l', '|', 0.25),
+    ('o', '0', 0.25),
+    ('s', '5', 0.25),
+    ('s', '$', 0.25),
+    ('t', '7', 0.25),
+    ('t', '+', 0.25),
+    ('z', '2', 0.25),
+];
+
+/// How much changing the case of a letter costs in the weighted edit distance
+pub const CASE_COST: f64 = 0.25;
+
+/// How the similarity of two strings is computed
+#[derive(
+    clap::ValueEnum,
+    serde::Deserialize,
+    serde::Serialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Algorithm {
+    /// The length of the longest common subsequence relative to the longer string, every
+    /// differing character counting the same
+    #[default]
+    Lcs,
+    /// An edit distance in which the substitutions in [`SUBSTITUTIONS`] and case changes cost
+    /// less than other edits
+    Weighted,
+}
+
+impl Algorithm {
+    /// The similarity of `left` and `right` in the 0-1 range, 1 if they are identical
+    ///
+    /// ```
+    /// use passchecker::Algorithm;
+    ///
+    /// let lcs = Algorithm::Lcs.similarity("password", "passw0rd");
+    /// let weighted = Algorithm::Weighted.similarity("password", "passw0rd");
+    /// assert_eq!(lcs, 0.875);
+    /// assert!(weighted > lcs);
+    /// assert!(Algorithm::Weighted.similarity("password", "passwxrd") < weighted);
+    /// assert_eq!(Algorithm::Weighted.similarity("password", "password"), 1.0);
+    /// ```
+    pub fn similarity(self, left: &str, right: &str) -> f64 {
+        match self {
+            Algorithm::Lcs => compare_similarity(left, right),
+            Algorithm::Weighted => weighted_similarity(left, right),
+        }
+    }
+}
+
+/// The cost of substituting `right` for `left`
+fn substitution_cost(left: char, right: char) -> f64 {
+    if left == right {
+        return 0.0;
+    }
+    let (lower_left, lower_right) = (left.to_ascii_lowercase(), right.to_ascii_lowercase());
+    if lower_left == lower_right {
+        return CASE_COST;
+    }
+    SUBSTITUTIONS
+        .iter()
+        .find(|(a, b, _)| {
+            (*a, *b) == (lower_left, lower_right) || (*b, *a) == (lower_left, lower_right)
+        })
+        .map_or(1.0, |(_, _, cost)| *cost)
+}
+
+/// 1 minus the weighted edit distance of `left` and `right` relative to the length of the longer
+/// one, insertions and deletions costing 1
+fn weighted_similarity(left: &str, right: &str) -> f64 {
+    let left: Vec<char> = left.chars().collect();
+    let right: Vec<char> = right.chars().collect();
+    let size = left.len().max(right.len());
+    if size == 0 {
+        return 1.0;
+    }
+    // The distances from the prefixes of `left` to the previous and the current prefix of `right`
+    let mut previous: Vec<f64> = (0..=left.len()).map(|length| length as f64).collect();
+    let mut current = vec![0.0; left.len() + 1];
+    for (row, &right_char) in right.iter().enumerate() {
+        current[0] = (row + 1) as f64;
+        for (column, &left_char) in left.iter().enumerate() {
+            let substitution = previous[column] + substitution_cost(left_char, right_char);
+            let deletion = previous[column + 1] + 1.0;
+            let insertion = current[column] + 1.0;
+            current[column + 1] = substitution.min(deletion).min(insertion);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    1.0 - previous[left.len()] / size as f64
+}
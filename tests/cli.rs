@@ -0,0 +1,956 @@
+//! Runs the binary on representative passwords and compares its whole output, uncolored, against
+//! what it is expected to print. The wordlist is a small fixture so the results don't depend on
+//! the internal wordlist.
+use std::process::Command;
+
+/// Runs passchecker against the fixture wordlist, returning its STDOUT and exit code
+fn passchecker(args: &[&str]) -> (String, i32) {
+    passchecker_with_env(args, &[])
+}
+
+/// Runs passchecker like [`passchecker`] with the environment variables `vars` set
+fn passchecker_with_env(args: &[&str], vars: &[(&str, &str)]) -> (String, i32) {
+    let output = Command::new(env!("CARGO_BIN_EXE_passchecker"))
+        .envs(vars.iter().copied())
+        .args(["--no-color", "--quiet", "--wordlist"])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/wordlist.txt"))
+        .args(args)
+        .output()
+        .expect("Failed to run passchecker");
+    let stdout = String::from_utf8(output.stdout).expect("Output is UTF-8");
+    (stdout, output.status.code().expect("Exited normally"))
+}
+
+#[test]
+fn every_test_passes() {
+    let (stdout, code) = passchecker(&["Xq7#mK9!vR2$wL"]);
+    assert_eq!(
+        stdout,
+        "\
+Password:                             Xq7#mK9!vR2$wL
+At least 8 characters:                success
+numbers:                              success
+quirky characters:                    success
+collisions in wordlist:               success
+Additional info: No significant match found in wordlist
+normalized collisions in wordlist:    success
+repeated patterns:                    success
+at most 40% one character:            success
+compresses to at least 50%:           success
+at most 70% dictionary word:          success
+at least 10^10 guesses:               success
+Additional info: Estimated 10^18.9 guesses: Xq7#mK9!vR2$wL (bruteforce)
+personal data:                        success
+default passwords:                    success
+Passed 12 out of 12 tests (100%), 0 ignored
+Strength: Very Strong (10^18.9 guesses)
+"
+    );
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn composition_and_pattern_tests_fail() {
+    let (stdout, _) = passchecker(&["abcabc"]);
+    assert_eq!(
+        stdout,
+        "\
+Password:                             abcabc
+At least 8 characters:                failure
+Additional info: Password too short: 6/8 characters
+numbers:                              failure
+Additional info: No numeric chacacters in password
+quirky characters:                    failure
+Additional info: No special chacacters in password
+collisions in wordlist:               success
+Additional info: No significant match found in wordlist
+normalized collisions in wordlist:    success
+repeated patterns:                    failure
+Additional info: Password is abc repeated 2 times
+at most 40% one character:            success
+compresses to at least 50%:           success
+at most 70% dictionary word:          success
+at least 10^10 guesses:               failure
+Additional info: Estimated 10^2.2 guesses: abc (sequence) + abc (sequence)
+personal data:                        success
+default passwords:                    success
+Passed 7 out of 12 tests (58.333332%), 0 ignored
+Strength: Very Weak (10^2.2 guesses)
+Suggestion: Avoid repeating the same pattern
+"
+    );
+}
+
+#[test]
+fn wordlist_tests_fail() {
+    let (stdout, _) = passchecker(&["letmein"]);
+    assert_eq!(
+        stdout,
+        "\
+Password:                             letmein
+At least 8 characters:                failure
+Additional info: Password too short: 7/8 characters
+numbers:                              failure
+Additional info: No numeric chacacters in password
+quirky characters:                    failure
+Additional info: No special chacacters in password
+collisions in wordlist:               failure
+Additional info: Best match in wordlist is letmein with similarity 100.0%
+normalized collisions in wordlist:    failure
+Additional info: Matches the wordlist entry letmein once lowercased
+repeated patterns:                    success
+at most 40% one character:            success
+compresses to at least 50%:           success
+at most 70% dictionary word:          failure
+Additional info: The wordlist entry letmein makes up 100.0% of the password
+at least 10^10 guesses:               failure
+Additional info: Estimated 10^0.7 guesses: letmein (wordlist entry ranked 5)
+personal data:                        success
+default passwords:                    failure
+Additional info: Password is the default password letmein
+Passed 4 out of 12 tests (33.333336%), 0 ignored
+Strength: Very Weak (10^0.7 guesses)
+Suggestion: Replace the default password letmein with one of your own
+"
+    );
+}
+
+#[test]
+fn partial_collision_similarity() {
+    let (stdout, _) = passchecker(&["letmein12"]);
+    assert!(stdout.contains(
+        "\
+collisions in wordlist:               success
+Additional info: Best match in wordlist is letmein with similarity 77.8%
+"
+    ));
+}
+
+#[test]
+fn personal_data_fails() {
+    let (stdout, _) = passchecker(&["555-123-4567"]);
+    assert!(stdout.contains(
+        "\
+personal data:                        failure
+Additional info: Password looks like a phone number
+default passwords:                    success
+Passed 10 out of 12 tests (83.33333%), 0 ignored
+Strength: Fair (10^7.2 guesses)
+"
+    ));
+}
+
+#[test]
+fn character_share_fails() {
+    let (stdout, _) = passchecker(&["a1a2a3a4a5"]);
+    assert!(stdout.contains(
+        "\
+at most 40% one character:            failure
+Additional info: The character 'a' makes up 50.0% of the password
+"
+    ));
+}
+
+#[test]
+fn compressibility_fails() {
+    let (stdout, _) = passchecker(&["Ab1!Ab1!Ab1!Ab1!Ab1!Ab1!x"]);
+    assert!(stdout.contains(
+        "\
+compresses to at least 50%:           failure
+Additional info: Long but low-entropy, the 25 characters of the password compress to 28.0% of their length
+"
+    ));
+}
+
+#[test]
+fn ignored_tests() {
+    let (stdout, code) = passchecker(&["-i", "numbers", "-i", "special-chars", "Xq7mK9vR2wLtz"]);
+    assert_eq!(
+        stdout,
+        "\
+Password:                             Xq7mK9vR2wLtz
+At least 8 characters:                success
+numbers:                              ignored
+Additional info: disabled with -i Numbers
+quirky characters:                    ignored
+Additional info: disabled with -i SpecialChars
+collisions in wordlist:               success
+Additional info: No significant match found in wordlist
+normalized collisions in wordlist:    success
+repeated patterns:                    success
+at most 40% one character:            success
+compresses to at least 50%:           success
+at most 70% dictionary word:          success
+at least 10^10 guesses:               success
+Additional info: Estimated 10^17.1 guesses: Xq7mK9vR2wLtz (bruteforce)
+personal data:                        success
+default passwords:                    success
+Passed 10 out of 10 tests (100%), 2 ignored
+Strength: Very Strong (10^17.1 guesses)
+"
+    );
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn status_symbols() {
+    let (stdout, _) = passchecker(&["--symbols", "-i", "numbers", "abcabc"]);
+    assert!(stdout.contains(
+        "\
+At least 8 characters:                ✗ failure
+Additional info: Password too short: 6/8 characters
+numbers:                              – ignored
+"
+    ));
+    assert!(stdout.contains("normalized collisions in wordlist:    ✓ success\n"));
+}
+
+#[test]
+fn check_exit_codes() {
+    assert_eq!(
+        passchecker(&["--check", "Xq7#mK9!vR2$wL"]),
+        (String::new(), 0)
+    );
+    assert_eq!(passchecker(&["--check", "Password1!"]), (String::new(), 2));
+}
+
+#[test]
+fn json_schema_version() {
+    let (stdout, _) = passchecker(&["--format", "json", "Xq7#mK9!vR2$wL"]);
+    let version = passchecker::report::SCHEMA_VERSION;
+    assert!(stdout.starts_with(&format!("{{\n  \"schema_version\": \"{version}\",\n")));
+}
+
+#[test]
+fn summary_counts() {
+    let args = [
+        "-i",
+        "numbers",
+        "-i",
+        "pattern-score",
+        "--advisory",
+        "special-chars",
+    ];
+    let (stdout, _) = passchecker(&[&args[..], &["abcabc"]].concat());
+    assert!(stdout.contains("Passed 7 out of 10 tests (70%), 1 advisory failed, 2 ignored\n"));
+    let (stdout, _) = passchecker(&[&args[..], &["--format", "json", "abcabc"]].concat());
+    assert!(stdout.contains(
+        "  \"passed\": 7,\n  \"warnings\": 0,\n  \"failed\": 2,\n  \"ignored\": 2,\n  \"advisory\": 1,\n"
+    ));
+}
+
+#[test]
+fn transformed_collisions_fail() {
+    let (stdout, _) = passchecker(&["--transformations", "rot13", "cnffjbeq"]);
+    assert!(stdout.contains(
+        "\
+transformed collisions in wordlist:    failure
+Additional info: Best match in wordlist for the ROT13 password is password with similarity 100.0%
+"
+    ));
+}
+
+#[test]
+fn reversed_collisions_fail_once() {
+    let (stdout, _) = passchecker(&["--transformations", "rot13", "drowssap"]);
+    assert!(stdout.contains(
+        "\
+normalized collisions in wordlist:     failure
+Additional info: Matches the wordlist entry password once reversed
+"
+    ));
+    assert!(stdout.contains("transformed collisions in wordlist:    success\n"));
+}
+
+#[test]
+fn longest_substring_in_characters() {
+    let substrings = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/substrings.txt");
+    let args = ["--wordlist", substrings, "--substrings", "парольsunshine1"];
+    let (stdout, _) = passchecker(&args);
+    assert!(
+        stdout.contains("Additional info: Contains the wordlist entry sunshine1 at character 7\n")
+    );
+}
+
+#[test]
+fn advisory_failures_dont_reject() {
+    let args = ["--advisory", "special-chars", "Xq7mKvRwLtzab"];
+    let (stdout, _) = passchecker(&args);
+    assert!(stdout.contains(
+        "\
+quirky characters:                    advisory
+Additional info: No special chacacters in password
+"
+    ));
+    assert!(
+        stdout.contains("Passed 11 out of 12 tests (91.66667%), 1 advisory failed, 0 ignored\n")
+    );
+    assert_eq!(passchecker(&[&["--check"], &args[..]].concat()).1, 0);
+}
+
+#[test]
+fn lint_wordlist() {
+    let (stdout, code) = passchecker(&[
+        "lint-wordlist",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/wordlist.txt"),
+    ]);
+    assert!(stdout.contains(
+        "\
+Lines:          5
+Entries:        5
+Unique entries: 5
+Duplicates:     0
+"
+    ));
+    assert!(stdout.ends_with(
+        "\
+Most common:    \"password\", \"123456\", \"qwerty\", \"dragon\", \"letmein\"
+Least common:   \"password\", \"123456\", \"qwerty\", \"dragon\", \"letmein\"
+"
+    ));
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn junit_report() {
+    let (stdout, _) = passchecker(&["--format", "junit", "-i", "numbers", "letmein"]);
+    assert!(stdout.starts_with(
+        "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<testsuites>
+  <testsuite name=\"letmein\" tests=\"12\" failures=\"7\" errors=\"0\" skipped=\"1\">
+    <testcase name=\"At least 8 characters\" classname=\"minimum-chars\">
+      <failure message=\"Password too short: 7/8 characters\">Password too short: 7/8 characters</failure>
+    </testcase>
+    <testcase name=\"numbers\" classname=\"numbers\">
+      <skipped message=\"disabled with -i Numbers\"/>
+    </testcase>
+"
+    ));
+    assert!(stdout.ends_with("  </testsuite>\n</testsuites>\n"));
+}
+
+#[test]
+fn leet_collisions_fail() {
+    let (stdout, _) = passchecker(&["--leet-collisions", "L3tM3!n"]);
+    assert!(stdout.contains(
+        "\
+leet variants of wordlist entries:    failure
+Additional info: Password is the wordlist entry letmein with look-alike substitutions
+"
+    ));
+}
+
+#[test]
+fn time_budget_skips_tests() {
+    let (stdout, _) = passchecker(&["--max-time-ms", "0", "Xq7#mK9!vR2"]);
+    assert!(stdout.contains(
+        "\
+personal data:                        ignored
+Additional info: Skipped, the time budget was used up
+"
+    ));
+    assert!(stdout.contains("Passed 0 out of 0 tests (100%), 12 ignored\n"));
+    assert!(stdout.ends_with("Skipped 12 tests, the --max-time-ms budget was used up\n"));
+    let (_, code) = passchecker(&["--check", "--max-time-ms", "0", "Xq7#mK9!vR2"]);
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn policy_from_environment() {
+    let vars = [
+        ("PASSCHECKER_MIN_LENGTH", "12"),
+        ("PASSCHECKER_IGNORE", "numbers,special-chars"),
+    ];
+    let (stdout, _) = passchecker_with_env(&["letmein"], &vars);
+    assert!(stdout.starts_with(
+        "\
+Password:                             letmein
+At least 12 characters:               failure
+Additional info: Password too short: 7/12 characters
+numbers:                              ignored
+Additional info: disabled with -i Numbers
+"
+    ));
+    let (stdout, _) = passchecker_with_env(&["--min-length", "4", "letmein"], &vars);
+    assert!(stdout.contains("At least 4 characters:                success\n"));
+}
+
+#[test]
+fn confirm_without_terminal() {
+    let (stdout, code) = passchecker(&["--confirm", "letmein"]);
+    assert!(!stdout.contains("proceed anyway"));
+    assert_eq!(code, 2);
+    assert_eq!(passchecker(&["--confirm", "Xq7#mK9!vR2$wL"]).1, 0);
+}
+
+#[test]
+fn cracklib_dictionary_fails() {
+    let dictionary = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/cracklib_dict");
+    let (stdout, _) = passchecker(&["--cracklib-dict", dictionary, "Sunshine"]);
+    assert!(stdout.contains(&format!(
+        "\
+cracklib dictionary:                  failure
+Additional info: Best match in the cracklib dictionary {dictionary} is sunshine with similarity 100.0%
+"
+    )));
+}
+
+#[test]
+fn service_rules() {
+    let (stdout, _) = passchecker(&["--service", "aws", "Xq7mkvrwltzab"]);
+    assert!(stdout.contains(
+        "\
+accepted by aws:                      success
+Additional info: Accepted by AWS IAM, met at least 8 characters, at most 128 characters, 3 of lowercase letters, uppercase letters, numbers and symbols
+"
+    ));
+    let (stdout, _) = passchecker(&["--service", "apple", "xq7#mkvrwltzab"]);
+    assert!(stdout.contains(
+        "\
+accepted by apple:                    failure
+Additional info: Rejected by Apple Account, missing an uppercase letter; met at least 8 characters, a lowercase letter, a number
+"
+    ));
+}
+
+#[test]
+fn dump_matches() {
+    let (stdout, code) = passchecker(&["--dump-matches", "40", "letmein2"]);
+    assert_eq!(stdout, "Password:    letmein2\nletmein:     87.5%\n");
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn json_strength_tier() {
+    let (stdout, _) = passchecker(&["--format", "json", "letmein"]);
+    assert!(stdout.contains("\"tier\": \"very-weak\""));
+}
+
+#[test]
+fn wordlist_stop_on_hit() {
+    let wordlist = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/wordlist.txt");
+    let (stdout, _) = passchecker(&["--wordlist-stop-on-hit", "-w", wordlist, "letmein"]);
+    assert!(stdout.contains(&format!(
+        "\
+collisions in wordlist:               failure
+Additional info: Best match in wordlist {wordlist} is letmein with similarity 100.0%
+"
+    )));
+}
+
+#[test]
+fn username_look_alikes() {
+    let (stdout, _) = passchecker(&["--username", "johnsmith", "Xq7#j0hnsm1th"]);
+    assert!(stdout.contains(
+        "\
+username and email address:           failure
+Additional info: Password contains the username johnsmith
+"
+    ));
+    let (stdout, _) = passchecker(&["--email", "john.smith@example.com", "J0hn.Sm1th!"]);
+    assert!(stdout.contains("Password contains the email address john.smith\n"));
+}
+
+#[cfg(feature = "embedded-wordlist")]
+#[test]
+fn export_wordlist() {
+    let output = concat!(env!("CARGO_TARGET_TMPDIR"), "/exported-wordlist.txt");
+    let (stdout, code) = passchecker(&["export-wordlist", output]);
+    assert!(stdout.starts_with("Exported "));
+    assert_eq!(code, 0);
+    let exported = std::fs::read_to_string(output).expect("The wordlist was exported");
+    assert!(exported.starts_with("password\n123456\n"));
+    assert!(exported.ends_with('\n') && !exported.contains('\r'));
+}
+
+#[test]
+fn plain_output_is_ascii() {
+    let (stdout, _) = passchecker(&["--plain", "--symbols", "letmein"]);
+    assert!(stdout.is_ascii() && !stdout.contains('\u{1b}'));
+    assert!(stdout.contains("collisions in wordlist:               x failure\n"));
+}
+
+#[test]
+fn by_severity() {
+    let (stdout, _) = passchecker(&["--by-severity", "letmein"]);
+    assert!(stdout.contains(
+        "\
+Password:                             letmein
+collisions in wordlist:               failure (critical)
+"
+    ));
+    assert!(stdout.contains("numbers:                              failure (low)\n"));
+}
+
+#[test]
+fn confusables() {
+    let (stdout, _) = passchecker(&["--check-confusables", "lеtmеin"]);
+    assert!(stdout.contains(
+        "\
+look-alikes from other scripts:       failure
+Additional info: Password written in ASCII matches the wordlist entry letmein with similarity 100.0%
+"
+    ));
+    let (stdout, _) = passchecker(&["--check-confusables", "Xq7#mK9!vR2$wLх"]);
+    assert!(
+        stdout.contains("Additional info: Password contains 1 character that looks like ASCII\n")
+    );
+}
+
+#[test]
+fn sha1_hash_only() {
+    let pwned = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/pwned");
+    let hash = "5baa61e4c9b93f3f0682250b6cf8331b7ee68fd8";
+    let (stdout, _) = passchecker(&["--pwned-dir", pwned, "--sha1-hash", hash]);
+    assert!(stdout.contains(
+        "\
+numbers:                              ignored
+Additional info: Skipped, only the SHA-1 hash of the password was given
+"
+    ));
+    assert!(stdout.contains(
+        "\
+breached passwords:                   failure
+Additional info: Seen 9545824 times in Pwned Passwords breaches
+Passed 0 out of 1 tests (0%), 12 ignored
+Only the SHA-1 hash was given, 12 tests need the plaintext password
+"
+    ));
+    let args = ["--check", "--pwned-dir", pwned, "--sha1-hash", hash];
+    assert_eq!(passchecker(&args).1, 2);
+    assert_eq!(passchecker(&["--sha1-hash", "5baa61e4"]).1, 2);
+}
+
+#[test]
+fn csv_delimiter() {
+    let (stdout, _) = passchecker(&["--format", "csv", "--delimiter", "semicolon", "a;b"]);
+    assert!(stdout.starts_with(
+        "\
+password;test;name;outcome;info
+\"a;b\";minimum-chars;At least 8 characters;failure;Password too short: 3/8 characters
+"
+    ));
+    let args = [
+        "--format",
+        "csv",
+        "--delimiter",
+        "tab",
+        "--no-header",
+        "a;b",
+    ];
+    let (stdout, _) = passchecker(&args);
+    assert!(stdout.starts_with("a;b\tminimum-chars\tAt least 8 characters\tfailure\t"));
+}
+
+#[test]
+fn crack_times() {
+    let args = ["--crack-times", "--offline-rate", "1000", "Tr0ub4dor&3"];
+    let (stdout, _) = passchecker(&args);
+    assert!(stdout.contains(
+        "\
+Crack time online:  centuries at 100 guesses per second
+Crack time offline: centuries at 1000 guesses per second
+"
+    ));
+    let (stdout, _) = passchecker(&["--format", "json", "letmein"]);
+    assert!(stdout.contains("\"offline\": {\n      \"guesses_per_second\": 10000000000,"));
+}
+
+#[test]
+fn min_tier_gate() {
+    let (stdout, code) = passchecker(&["--min-tier", "strong", "qzvxkwjmtrplbhdn"]);
+    assert!(stdout.contains("Strength: Very Strong (10^22.6 guesses), at least Strong required\n"));
+    assert_eq!(code, 0);
+    let (stdout, code) = passchecker(&["--min-tier", "strong", "letmein"]);
+    assert!(stdout.contains("Strength: Very Weak (10^0.7 guesses), at least Strong required\n"));
+    assert_eq!(code, 2);
+}
+
+#[test]
+fn no_match_details() {
+    let (stdout, _) = passchecker(&["--no-match-details", "letmein"]);
+    assert!(stdout.contains(
+        "\
+collisions in wordlist:               failure
+normalized collisions in wordlist:    failure
+repeated patterns:"
+    ));
+    let (stdout, _) = passchecker(&["--no-match-details", "--show-match-rank", "letmein"]);
+    assert!(stdout.contains("collisions in wordlist:               failure\nAdditional info: "));
+}
+
+#[test]
+fn dedup_wordlist() {
+    let wordlist = concat!(env!("CARGO_TARGET_TMPDIR"), "/duplicates.txt");
+    std::fs::write(wordlist, "dragon\ndragon\nqwerty\ndragon\nsunflower\n")
+        .expect("Wrote wordlist");
+    let rank = |dedup: &[&str]| {
+        let args = [dedup, &["--show-match-rank", "-w", wordlist, "sunflower"]].concat();
+        let (stdout, _) = passchecker(&args);
+        stdout.contains("the #3 most common entry")
+    };
+    assert!(!rank(&[]));
+    assert!(rank(&["--dedup-wordlist"]));
+}
+
+#[test]
+fn word_pairs() {
+    let (stdout, _) = passchecker(&["--word-pairs", "dragonqwerty"]);
+    assert!(stdout.contains(
+        "pairs of wordlist entries:            failure\nAdditional info: Password is the wordlist entries dragon + qwerty with similarities 100.0% and 100.0%\n"
+    ));
+    let (stdout, _) = passchecker(&["--word-pairs", "dragonhorse"]);
+    assert!(stdout.contains("pairs of wordlist entries:            success\n"));
+}
+
+#[test]
+fn wordlist_min_length() {
+    let collides = |args: &[&str]| {
+        let (stdout, _) = passchecker(&[args, &["dragon"]].concat());
+        stdout.contains("collisions in wordlist:               failure\n")
+    };
+    assert!(collides(&[]));
+    assert!(!collides(&["--wordlist-min-length", "7"]));
+}
+
+#[test]
+fn policy_json() {
+    let (stdout, _) = passchecker(&[
+        "--policy-json",
+        r#"{"minimum-chars": {"length": 30}}"#,
+        "Xq7#mK9!vR2$wL",
+    ]);
+    assert!(stdout.contains("At least 30 characters:               failure\n"));
+    let output = Command::new(env!("CARGO_BIN_EXE_passchecker"))
+        .args([
+            "--policy-json",
+            r#"{"minimum-chars": {"lenght": 30}}"#,
+            "Xq7#mK9!vR2$wL",
+        ])
+        .output()
+        .expect("Failed to run passchecker");
+    let stderr = String::from_utf8(output.stderr).expect("Output is UTF-8");
+    assert!(stderr.contains("Failed to parse --policy-json: unknown field `lenght`"));
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn banned_terms() {
+    let (_, code) = passchecker(&["--check", "--ban", "ACME", "xxAcme12345"]);
+    assert_eq!(code, 2);
+    let (stdout, _) = passchecker(&["--ban", "widget,ACME", "xxAcme12345"]);
+    assert!(stdout.starts_with(
+        "\
+Password:                             xxAcme12345
+banned terms:                         failure
+Additional info: Password contains the banned term ACME
+At least 8 characters:                ignored
+Additional info: Skipped, the password contains a banned term
+"
+    ));
+    assert!(stdout.contains("Passed 0 out of 1 tests (0%), 12 ignored\n"));
+}
+
+#[test]
+fn default_passwords() {
+    let (stdout, _) = passchecker(&["Changeme1!"]);
+    assert!(stdout.contains(
+        "\
+default passwords:                    failure
+Additional info: Password is close to the default password changeme
+"
+    ));
+    let (stdout, _) = passchecker(&["--default-password", "acme-setup", "Acme-Setup"]);
+    assert!(stdout.contains("Additional info: Password is the default password acme-setup\n"));
+}
+
+#[test]
+fn length_counts_characters() {
+    let (stdout, _) = passchecker(&["--checklist", "пароль"]);
+    assert!(stdout.contains(
+        "\
+At least 8 characters:                failure
+Additional info: Password too short: 6/8 characters
+"
+    ));
+    assert!(stdout.contains("☐ Add 2 more characters\n"));
+}
+
+#[test]
+fn mask_hides_banned_term() {
+    for args in [&[][..], &["--checklist"], &["--format", "json"]] {
+        let args = [&["--mask", "--ban", "sup3rsecret"], args, &["sup3rsecret"]].concat();
+        let (stdout, _) = passchecker(&args);
+        assert!(stdout.contains("s*********t"));
+        assert!(!stdout.contains("sup3rsecret"));
+    }
+}
+
+#[test]
+fn mask_hides_context_term() {
+    let (stdout, _) = passchecker(&["--mask", "--context", "sup3rsecret", "sup3rsecret"]);
+    assert!(stdout.contains("Password contains the context term s*********t\n"));
+    assert!(!stdout.contains("sup3rsecret"));
+}
+
+#[test]
+fn mask_hides_username() {
+    let (stdout, _) = passchecker(&["--mask", "--username", "sup3rsecret", "sup3rsecret"]);
+    assert!(stdout.contains("Password contains the username s*********t\n"));
+    assert!(!stdout.contains("sup3rsecret"));
+}
+
+#[test]
+fn empty_wordlist_from_stdin() {
+    let output = Command::new(env!("CARGO_BIN_EXE_passchecker"))
+        .args(["--no-color", "--quiet", "--wordlist", "-", "Xq7#mK9!vR2$wL"])
+        .stdin(std::process::Stdio::null())
+        .output()
+        .expect("Failed to run passchecker");
+    let stdout = String::from_utf8(output.stdout).expect("Output is UTF-8");
+    assert!(stdout.contains(
+        "\
+collisions in wordlist:               ignored
+Additional info: The wordlist has no entries to compare the password against
+"
+    ));
+}
+
+#[test]
+fn profile_nist() {
+    let (stdout, _) = passchecker(&["--profile", "nist", "Xq7#mK9!vR2$wLpz"]);
+    assert_eq!(
+        stdout,
+        "\
+Password:                                     Xq7#mK9!vR2$wLpz
+At least 8 characters:                        success
+numbers:                                      ignored
+Additional info: disabled with -i Numbers
+quirky characters:                            ignored
+Additional info: disabled with -i SpecialChars
+collisions in wordlist:                       success
+Additional info: No significant match found in wordlist
+normalized collisions in wordlist:            success
+repeated patterns:                            success
+at most 40% one character:                    success
+compresses to at least 50%:                   success
+at most 70% dictionary word:                  success
+at least 10^10 guesses:                       success
+Additional info: Estimated 10^21.7 guesses: Xq7#mK9!vR2$wLpz (bruteforce)
+personal data:                                success
+default passwords:                            success
+wordlist entries of at least 4 characters:    success
+leet variants of wordlist entries:            success
+Passed 12 out of 12 tests (100%), 2 ignored
+Strength: Very Strong (10^21.7 guesses)
+"
+    );
+}
+
+#[test]
+fn profile_pci() {
+    let (stdout, _) = passchecker(&["--profile", "pci", "Xq7#mK9!vR2$wLpz"]);
+    assert_eq!(
+        stdout,
+        "\
+Password:                             Xq7#mK9!vR2$wLpz
+At least 12 characters:               success
+numbers:                              success
+quirky characters:                    ignored
+Additional info: disabled with -i SpecialChars
+collisions in wordlist:               success
+Additional info: No significant match found in wordlist
+normalized collisions in wordlist:    success
+repeated patterns:                    success
+at most 40% one character:            success
+compresses to at least 50%:           success
+at most 70% dictionary word:          success
+at least 10^10 guesses:               success
+Additional info: Estimated 10^21.7 guesses: Xq7#mK9!vR2$wLpz (bruteforce)
+personal data:                        success
+default passwords:                    success
+Passed 11 out of 11 tests (100%), 1 ignored
+Strength: Very Strong (10^21.7 guesses)
+"
+    );
+}
+
+#[test]
+fn profile_strict() {
+    let (stdout, _) = passchecker(&["--profile", "strict", "Xq7#mK9!vR2$wLpz"]);
+    assert_eq!(
+        stdout,
+        "\
+Password:                                     Xq7#mK9!vR2$wLpz
+At least 16 characters:                       success
+numbers:                                      success
+quirky characters:                            success
+collisions in wordlist:                       success
+Additional info: No significant match found in wordlist
+normalized collisions in wordlist:            success
+repeated patterns:                            success
+at most 40% one character:                    success
+compresses to at least 50%:                   success
+at most 70% dictionary word:                  success
+at least 10^10 guesses:                       success
+Additional info: Estimated 10^21.7 guesses: Xq7#mK9!vR2$wLpz (bruteforce)
+personal data:                                success
+default passwords:                            success
+wordlist entries of at least 4 characters:    success
+leet variants of wordlist entries:            success
+look-alikes from other scripts:               success
+Passed 15 out of 15 tests (100%), 0 ignored
+Strength: Very Strong (10^21.7 guesses)
+"
+    );
+}
+
+#[test]
+fn checklist() {
+    let (stdout, _) = passchecker(&[
+        "--checklist",
+        "--min-length",
+        "12",
+        "--ban",
+        "pass",
+        "qzvxkwjm",
+    ]);
+    assert!(stdout.ends_with(
+        "\
+Checklist:
+☐ Add 4 more characters
+☐ Add a number
+☐ Add a special character
+"
+    ));
+    let (stdout, _) = passchecker(&["--checklist", "--ban", "kwj", "qzvxkwjm"]);
+    assert!(stdout.contains("Checklist:\n☐ Remove the banned term kwj\n"));
+}
+
+#[test]
+fn breach_counts() {
+    let counts = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/breach-counts.txt");
+    let breach_counts = |args: &[&str]| {
+        let (stdout, _) = passchecker(&[&["--breach-counts", counts], args].concat());
+        let start = stdout.find("breach counts:").expect("The test ran");
+        stdout[start..]
+            .lines()
+            .take(2)
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    assert_eq!(
+        breach_counts(&["dragon"]),
+        format!("breach counts:                        failure\nAdditional info: Seen 24601 times in {counts}, over the threshold of 10000")
+    );
+    assert_eq!(
+        breach_counts(&["sunflower"]),
+        format!("breach counts:                        success\nAdditional info: Seen 12 times in {counts}, within the threshold of 10000")
+    );
+    assert!(breach_counts(&["--breach-threshold", "10", "sunflower"]).contains("failure"));
+}
+
+#[test]
+fn no_color_overrides_color_depth() {
+    let args = [
+        "--color-depth",
+        "truecolor",
+        "--by-severity",
+        "Xq7#mK9!vR2$wL",
+    ];
+    let (stdout, _) = passchecker_with_env(&args, &[("FORCE_COLOR", "3")]);
+    assert!(stdout.contains("personal data:                        success (high)\n"));
+    assert!(!stdout.contains('\u{1b}'));
+}
+
+#[test]
+fn regex_formats() {
+    let one_symbol = "^[^@#$]*[@#$][^@#$]*$";
+    let args = ["--match-regex", one_symbol, "--reject-regex", "(?i)acme"];
+    let (stdout, _) = passchecker(&[&args[..], &["Xq7#mK9vR2"]].concat());
+    assert!(stdout.contains(&format!(
+        "\
+required formats:                     success
+Additional info: Password matches {one_symbol}
+rejected formats:                     success
+"
+    )));
+    let (stdout, _) = passchecker(&[&args[..], &["Xq7#mK9vR2#ACME"]].concat());
+    assert!(stdout.contains(&format!(
+        "\
+required formats:                     failure
+Additional info: Password doesn't match the required format {one_symbol}
+rejected formats:                     failure
+Additional info: Password matches the rejected format (?i)acme with ACME
+"
+    )));
+    let (stdout, code) = passchecker(&["--match-regex", "[a-", "Xq7#mK9vR2"]);
+    assert_eq!((stdout.as_str(), code), ("", 1));
+}
+
+#[test]
+fn empty_password_exit_codes() {
+    let (stdout, code) = passchecker(&["   "]);
+    assert_eq!(
+        (stdout.as_str(), code),
+        (
+            "failure The password only contains whitespace, not running any tests.\n",
+            0
+        )
+    );
+    assert_eq!(passchecker(&["--check", "   "]), (String::new(), 2));
+    assert_eq!(passchecker(&["--min-tier", "weak", "   "]).1, 2);
+    // Without a terminal to ask at the answer is no
+    assert_eq!(passchecker(&["--confirm", "   "]).1, 2);
+}
+
+#[test]
+fn ngram_entropy() {
+    let (stdout, _) = passchecker(&["--ngram-entropy", "thereason"]);
+    assert!(stdout.contains(
+        "\
+at least 40 bits of n-gram entropy:    failure
+Additional info: Estimated 24.6 bits from English letter pair frequencies
+"
+    ));
+    let (stdout, _) = passchecker(&["--ngram-entropy", "--min-ngram-bits", "60", "xqjzvkwpf"]);
+    assert!(stdout.contains(
+        "\
+at least 60 bits of n-gram entropy:    success
+Additional info: Estimated 69.9 bits from English letter pair frequencies
+"
+    ));
+}
+
+#[test]
+fn batch_report_with_skipped_passwords() {
+    let (stdout, _) = passchecker(&[
+        "--format",
+        "junit",
+        "--allowlist",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/allowlist.txt"),
+        "--batch",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/batch.txt"),
+    ]);
+    assert!(stdout.starts_with(
+        "\
+<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<testsuites>
+  <testsuite name=\"   \" tests=\"12\" failures=\"0\" errors=\"0\" skipped=\"12\">
+    <testcase name=\"At least 8 characters\" classname=\"minimum-chars\">
+      <skipped message=\"The password only contains whitespace\"/>
+"
+    ));
+    assert!(stdout.contains(
+        "\
+  <testsuite name=\"CorrectHorse42\" tests=\"12\" failures=\"0\" errors=\"0\" skipped=\"12\">
+    <testcase name=\"At least 8 characters\" classname=\"minimum-chars\">
+      <skipped message=\"The password is allowlisted\"/>
+"
+    ));
+    assert_eq!(stdout.matches("<testsuite ").count(), 3);
+    assert_eq!(stdout.matches("<testsuites>").count(), 1);
+    assert!(stdout.ends_with("  </testsuite>\n</testsuites>\n"));
+    assert!(!stdout.contains("not running any tests") && !stdout.contains("skipped all tests"));
+}